@@ -0,0 +1,8 @@
+//! Turns text into spoken audio.
+//!
+//! The speech endpoint answers with the audio file itself instead of JSON, so
+//! [`SpeechRequest`](speech::SpeechRequest) is sent through the
+//! [`Binary`](crate::rest::post::Binary) methods returning bytes rather than
+//! the [`NoStream`](crate::rest::post::NoStream) ones.
+
+pub mod speech;
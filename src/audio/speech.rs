@@ -0,0 +1,125 @@
+//! Generating audio from text, at `/audio/speech`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use openai_interface::audio::speech::{SpeechRequest, SpeechResponseFormat};
+//! use openai_interface::rest::post::Binary;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let request = SpeechRequest {
+//!         model: "gpt-4o-mini-tts".to_string(),
+//!         input: "The quick brown fox jumped over the lazy dog.".to_string(),
+//!         voice: "alloy".to_string(),
+//!         response_format: Some(SpeechResponseFormat::Mp3),
+//!         ..Default::default()
+//!     };
+//!
+//!     let mut file = tokio::fs::File::create("speech.mp3").await?;
+//!     request
+//!         .stream_to_writer("https://api.openai.com/v1/audio/speech", "sk-...", &mut file)
+//!         .await?;
+//!     Ok(())
+//! }
+//! ```
+
+use serde::Serialize;
+
+use crate::rest::post::{Binary, Post};
+
+#[derive(Debug, Serialize, Default, Clone)]
+pub struct SpeechRequest {
+    /// One of the available TTS models, e.g. `tts-1`, `tts-1-hd` or
+    /// `gpt-4o-mini-tts`.
+    pub model: String,
+    /// The text to generate audio for. The maximum length is 4096 characters.
+    pub input: String,
+    /// The voice to use, e.g. `alloy`, `echo`, `nova` or `shimmer`.
+    pub voice: String,
+    /// Control the voice of the generated audio with additional instructions.
+    /// Does not work with `tts-1` or `tts-1-hd`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    /// The format of the audio. Defaults to `mp3`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<SpeechResponseFormat>,
+    /// The speed of the generated audio, from 0.25 to 4.0. Defaults to 1.0.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<f32>,
+    /// Add additional JSON properties to the request
+    #[serde(flatten)]
+    pub extra_body: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SpeechResponseFormat {
+    Mp3,
+    Opus,
+    Aac,
+    Flac,
+    Wav,
+    Pcm,
+}
+
+impl Post for SpeechRequest {
+    fn is_streaming(&self) -> bool {
+        false
+    }
+
+    fn path(&self) -> Option<String> {
+        Some("/audio/speech".to_string())
+    }
+}
+
+impl Binary for SpeechRequest {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockResponse, MockServer};
+
+    #[tokio::test]
+    async fn binary_speech() {
+        let audio: Vec<u8> = b"ID3\x04\x00\x00\x00\x00\x00\xff\xfb\x90\x00"
+            .iter()
+            .cycle()
+            .take(50_000)
+            .copied()
+            .collect();
+        let server = MockServer::start(vec![
+            MockResponse::new(200, audio.clone()).with_header("Content-Type", "audio/mpeg"),
+        ])
+        .await;
+        let url = server.url("/v1/audio/speech");
+        let request = SpeechRequest {
+            model: "tts-1".to_string(),
+            input: "Hello!".to_string(),
+            voice: "alloy".to_string(),
+            response_format: Some(SpeechResponseFormat::Mp3),
+            speed: Some(1.5),
+            ..Default::default()
+        };
+
+        assert_eq!(request.get_bytes(&url, "key").await.unwrap(), audio);
+        let mut buffer = Vec::new();
+        let written = request
+            .stream_to_writer(&url, "key", &mut buffer)
+            .await
+            .unwrap();
+        assert_eq!(written, audio.len() as u64);
+        assert_eq!(buffer, audio);
+
+        assert_eq!(
+            server.requests()[0].body_json(),
+            serde_json::json!({
+                "model": "tts-1",
+                "input": "Hello!",
+                "voice": "alloy",
+                "response_format": "mp3",
+                "speed": 1.5,
+            })
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! Creating and polling batch jobs.
+//!
+//! A batch runs the requests of an uploaded `.jsonl` file asynchronously,
+//! within 24 hours and at a lower price. Upload the input with
+//! [`FilePurpose::Batch`](crate::files::create::request::FilePurpose::Batch),
+//! create the batch, then retrieve it until its [status](response::BatchStatus)
+//! is final and download the `output_file_id` with
+//! [`files::content`](crate::files::content()), or
+//! [`RetrieveFileContentRequest`](crate::files::content::RetrieveFileContentRequest)
+//! to stream a large output to a file.
+//!
+//! `url` in the functions below is the batches endpoint, e.g.
+//! `https://api.openai.com/v1/batches`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use openai_interface::batch::{self, request::CreateBatchRequest};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let url = "https://api.openai.com/v1/batches";
+//!     let request = CreateBatchRequest {
+//!         input_file_id: "file-abc123".to_string(),
+//!         endpoint: "/v1/chat/completions".to_string(),
+//!         ..Default::default()
+//!     };
+//!
+//!     let mut batch = batch::create_batch(url, "sk-...", &request).await?;
+//!     while !batch.status.is_final() {
+//!         tokio::time::sleep(Duration::from_secs(60)).await;
+//!         batch = batch::retrieve_batch(url, "sk-...", &batch.id).await?;
+//!     }
+//!     println!("Output file: {:?}", batch.output_file_id);
+//!     Ok(())
+//! }
+//! ```
+
+pub mod request;
+pub mod response;
+
+use futures_util::TryStreamExt;
+
+use crate::errors::OapiError;
+use crate::rest::get::Get;
+use crate::rest::post::NoStream;
+use request::{CancelBatchRequest, CreateBatchRequest, ListBatchesRequest, RetrieveBatchRequest};
+use response::BatchObject;
+
+/// Creates a batch from an uploaded input file.
+pub async fn create_batch(
+    url: &str,
+    key: &str,
+    request: &CreateBatchRequest,
+) -> Result<BatchObject, OapiError> {
+    request.get_response(url, key).await
+}
+
+/// Retrieves the batch `batch_id`, at `{url}/{batch_id}`.
+pub async fn retrieve_batch(
+    url: &str,
+    key: &str,
+    batch_id: &str,
+) -> Result<BatchObject, OapiError> {
+    RetrieveBatchRequest {
+        batch_id: batch_id.to_string(),
+    }
+    .get_response(url, key)
+    .await
+}
+
+/// Lists every batch of the organization, following the pages of the list
+/// endpoint.
+///
+/// All pages are held in memory. To handle the batches as the pages arrive,
+/// use [`ListBatchesRequest::stream_all`] instead.
+pub async fn list_batches(url: &str, key: &str) -> Result<Vec<BatchObject>, OapiError> {
+    ListBatchesRequest::default()
+        .stream_all(url, key)
+        .try_collect()
+        .await
+}
+
+/// Cancels the batch `batch_id`, at `{url}/{batch_id}/cancel`.
+///
+/// The batch is `cancelling` for up to 10 minutes before it is `cancelled`,
+/// with the results completed so far in its output file.
+pub async fn cancel_batch(url: &str, key: &str, batch_id: &str) -> Result<BatchObject, OapiError> {
+    let request = CancelBatchRequest {
+        batch_id: batch_id.to_string(),
+    };
+    request.get_response(&request.url(url), key).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockResponse, MockServer};
+    use response::BatchStatus;
+
+    fn batch(status: &str) -> String {
+        format!(
+            r#"{{"id":"batch_abc123","object":"batch","endpoint":"/v1/chat/completions","errors":null,"input_file_id":"file-abc123","completion_window":"24h","status":"{}","output_file_id":"file-cvaTdG","error_file_id":null,"created_at":1711471533,"in_progress_at":1711471538,"expires_at":1711557933,"completed_at":null,"request_counts":{{"total":100,"completed":95,"failed":0}},"metadata":{{"customer_id":"user_123"}}}}"#,
+            status
+        )
+    }
+
+    #[tokio::test]
+    async fn create_poll_and_cancel() {
+        let server = MockServer::start(vec![
+            MockResponse::json(200, &batch("validating")),
+            MockResponse::json(200, &batch("in_progress")),
+            MockResponse::json(
+                200,
+                &format!(
+                    r#"{{"object":"list","data":[{}],"has_more":false}}"#,
+                    batch("in_progress")
+                ),
+            ),
+            MockResponse::json(200, &batch("cancelling")),
+        ])
+        .await;
+        let url = server.url("/v1/batches");
+
+        let request = CreateBatchRequest {
+            input_file_id: "file-abc123".to_string(),
+            endpoint: "/v1/chat/completions".to_string(),
+            ..Default::default()
+        };
+        let created = create_batch(&url, "key", &request).await.unwrap();
+        assert_eq!(created.status, BatchStatus::Validating);
+        assert!(!created.status.is_final());
+
+        let batch = retrieve_batch(&url, "key", &created.id).await.unwrap();
+        assert_eq!(batch.status, BatchStatus::InProgress);
+        assert_eq!(batch.output_file_id.as_deref(), Some("file-cvaTdG"));
+        assert_eq!(batch.error_file_id, None);
+        let counts = batch.request_counts.unwrap();
+        assert_eq!(
+            (counts.total, counts.completed, counts.failed),
+            (100, 95, 0)
+        );
+
+        let batches = list_batches(&url, "key").await.unwrap();
+        assert_eq!(batches.len(), 1);
+
+        let cancelled = cancel_batch(&url, "key", &created.id).await.unwrap();
+        assert_eq!(cancelled.status, BatchStatus::Cancelling);
+
+        let requests = server.requests();
+        let calls: Vec<(&str, &str)> = requests
+            .iter()
+            .map(|r| (r.method.as_str(), r.path.as_str()))
+            .collect();
+        assert_eq!(
+            calls,
+            vec![
+                ("POST", "/v1/batches"),
+                ("GET", "/v1/batches/batch_abc123"),
+                ("GET", "/v1/batches"),
+                ("POST", "/v1/batches/batch_abc123/cancel"),
+            ]
+        );
+        assert_eq!(
+            requests[0].body_json(),
+            serde_json::json!({
+                "input_file_id": "file-abc123",
+                "endpoint": "/v1/chat/completions",
+                "completion_window": "24h",
+            })
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use futures_util::Stream;
+use serde::Serialize;
+
+use super::response::BatchObject;
+use crate::errors::OapiError;
+use crate::rest::get::Get;
+use crate::rest::pagination::{Page, paginate};
+use crate::rest::post::{NoStream, Post};
+
+/// Creates and executes a batch from an uploaded file of requests, at
+/// `/batches`.
+#[derive(Debug, Serialize, Clone)]
+pub struct CreateBatchRequest {
+    /// The ID of an uploaded file that contains requests for the new batch.
+    ///
+    /// The file must be a `.jsonl` file uploaded with the purpose `batch`, with up
+    /// to 50,000 requests and a size of up to 200 MB.
+    pub input_file_id: String,
+    /// The endpoint to be used for all requests in the batch, e.g.
+    /// `/v1/chat/completions`, `/v1/embeddings` or `/v1/completions`.
+    pub endpoint: String,
+    /// The time frame within which the batch should be processed. Currently
+    /// only `24h` is supported, which is the default.
+    pub completion_window: String,
+    /// Up to 16 key-value pairs attached to the batch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl Default for CreateBatchRequest {
+    fn default() -> Self {
+        Self {
+            input_file_id: String::new(),
+            endpoint: String::new(),
+            completion_window: "24h".to_string(),
+            metadata: None,
+        }
+    }
+}
+
+/// Retrieves a batch, at `/batches/{batch_id}`.
+#[derive(Debug, Clone, Default)]
+pub struct RetrieveBatchRequest {
+    /// The ID of the batch to retrieve.
+    pub batch_id: String,
+}
+
+/// Lists one page of batches, at `/batches`.
+///
+/// [`ListBatchesRequest::stream_all`] and
+/// [`batch::list_batches`](super::list_batches) fetch every page instead.
+#[derive(Debug, Clone, Default)]
+pub struct ListBatchesRequest {
+    /// The number of batches to return, between 1 and 100.
+    pub limit: Option<usize>,
+    /// The ID of the batch after which the page starts, for pagination.
+    pub after: Option<String>,
+}
+
+/// Cancels an in-progress batch, at `/batches/{batch_id}/cancel`.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct CancelBatchRequest {
+    /// The ID of the batch to cancel.
+    #[serde(skip_serializing)]
+    pub batch_id: String,
+}
+
+impl CancelBatchRequest {
+    /// The cancel URL of the batch, below the batches endpoint.
+    pub fn url(&self, endpoint: &str) -> String {
+        format!(
+            "{}/{}/cancel",
+            endpoint.trim_end_matches('/'),
+            self.batch_id
+        )
+    }
+}
+
+impl Post for CreateBatchRequest {
+    #[inline]
+    fn is_streaming(&self) -> bool {
+        false
+    }
+
+    fn path(&self) -> Option<String> {
+        Some("/batches".to_string())
+    }
+}
+
+impl NoStream for CreateBatchRequest {
+    type Response = BatchObject;
+}
+
+impl Post for CancelBatchRequest {
+    #[inline]
+    fn is_streaming(&self) -> bool {
+        false
+    }
+
+    fn path(&self) -> Option<String> {
+        Some(format!("/batches/{}/cancel", self.batch_id))
+    }
+}
+
+impl NoStream for CancelBatchRequest {
+    type Response = BatchObject;
+}
+
+impl Get for RetrieveBatchRequest {
+    type Response = BatchObject;
+
+    fn url(&self, endpoint: &str) -> String {
+        format!("{}/{}", endpoint.trim_end_matches('/'), self.batch_id)
+    }
+}
+
+impl ListBatchesRequest {
+    /// Streams every batch, fetching the next page of the batches endpoint at
+    /// `url` only when the items of the previous one have been consumed.
+    ///
+    /// The stream starts after `after` if it is set, and ends after the first
+    /// error.
+    pub fn stream_all<'a>(
+        &'a self,
+        url: &'a str,
+        key: &'a str,
+    ) -> impl Stream<Item = Result<BatchObject, OapiError>> + 'a {
+        paginate(move |after| {
+            let request = ListBatchesRequest {
+                after: after.or_else(|| self.after.clone()),
+                ..self.clone()
+            };
+            async move { request.get_response(url, key).await }
+        })
+    }
+}
+
+impl Get for ListBatchesRequest {
+    type Response = Page<BatchObject>;
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        let mut query = Vec::new();
+        if let Some(limit) = self.limit {
+            query.push(("limit", limit.to_string()));
+        }
+        if let Some(after) = &self.after {
+            query.push(("after", after.clone()));
+        }
+        query
+    }
+}
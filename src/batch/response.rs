@@ -0,0 +1,130 @@
+use std::{collections::HashMap, str::FromStr};
+
+use serde::Deserialize;
+
+use crate::errors::OapiError;
+use crate::rest::pagination::PageItem;
+
+/// A batch job and its progress.
+#[derive(Debug, Deserialize, Clone)]
+pub struct BatchObject {
+    pub id: String,
+    /// The object type, which is always `batch`.
+    pub object: String,
+    /// The API endpoint used by the batch.
+    pub endpoint: String,
+    /// The errors found while validating the input file.
+    pub errors: Option<BatchErrors>,
+    /// The ID of the input file for the batch.
+    pub input_file_id: String,
+    /// The time frame within which the batch should be processed.
+    pub completion_window: String,
+    /// The current status of the batch.
+    pub status: BatchStatus,
+    /// The ID of the file containing the outputs of successfully executed
+    /// requests.
+    pub output_file_id: Option<String>,
+    /// The ID of the file containing the outputs of requests with errors.
+    pub error_file_id: Option<String>,
+    /// The Unix timestamp (in seconds) for when the batch was created.
+    pub created_at: u64,
+    /// The Unix timestamp (in seconds) for when the batch started processing.
+    pub in_progress_at: Option<u64>,
+    /// The Unix timestamp (in seconds) for when the batch will expire.
+    pub expires_at: Option<u64>,
+    /// The Unix timestamp (in seconds) for when the batch started finalizing.
+    pub finalizing_at: Option<u64>,
+    /// The Unix timestamp (in seconds) for when the batch was completed.
+    pub completed_at: Option<u64>,
+    /// The Unix timestamp (in seconds) for when the batch failed.
+    pub failed_at: Option<u64>,
+    /// The Unix timestamp (in seconds) for when the batch expired.
+    pub expired_at: Option<u64>,
+    /// The Unix timestamp (in seconds) for when the batch started cancelling.
+    pub cancelling_at: Option<u64>,
+    /// The Unix timestamp (in seconds) for when the batch was cancelled.
+    pub cancelled_at: Option<u64>,
+    /// The request counts for different statuses within the batch.
+    pub request_counts: Option<BatchRequestCounts>,
+    /// The key-value pairs attached to the batch.
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchStatus {
+    /// The input file is being validated before the batch can begin.
+    Validating,
+    /// The input file has failed the validation process.
+    Failed,
+    /// The input file was successfully validated and the batch is running.
+    InProgress,
+    /// The batch has completed and the results are being prepared.
+    Finalizing,
+    /// The batch has been completed and the results are ready.
+    Completed,
+    /// The batch was not able to be completed within the completion window.
+    Expired,
+    /// The batch is being cancelled.
+    Cancelling,
+    /// The batch was cancelled.
+    Cancelled,
+    /// A status this crate does not know yet.
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl BatchStatus {
+    /// Whether the batch has stopped changing: it completed, failed, expired
+    /// or was cancelled. Unknown statuses are not final.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            Self::Failed | Self::Completed | Self::Expired | Self::Cancelled
+        )
+    }
+}
+
+/// The errors found while validating the input file of a batch.
+#[derive(Debug, Deserialize, Clone)]
+pub struct BatchErrors {
+    pub data: Vec<BatchError>,
+    pub object: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct BatchError {
+    /// An error code identifying the error type.
+    pub code: Option<String>,
+    /// The line number of the input file where the error occurred, if
+    /// applicable.
+    pub line: Option<u64>,
+    /// A human-readable message providing more details about the error.
+    pub message: Option<String>,
+    /// The name of the parameter that caused the error, if applicable.
+    pub param: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct BatchRequestCounts {
+    /// Total number of requests in the batch.
+    pub total: u64,
+    /// Number of requests that have been completed successfully.
+    pub completed: u64,
+    /// Number of requests that have failed.
+    pub failed: u64,
+}
+
+impl FromStr for BatchObject {
+    type Err = OapiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s).map_err(OapiError::from)
+    }
+}
+
+impl PageItem for BatchObject {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
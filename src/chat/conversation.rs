@@ -0,0 +1,314 @@
+//! Building a conversation history that providers will accept.
+//!
+//! Providers reject some message orders outright, such as a tool message that
+//! answers no tool call, and models behave poorly on others. [`Conversation`]
+//! collects messages and checks their order before they are sent.
+//!
+//! # Example
+//!
+//! ```rust
+//! use openai_interface::chat::conversation::Conversation;
+//! use openai_interface::chat::request::RequestBody;
+//!
+//! let messages = Conversation::new()
+//!     .system("You are a helpful assistant.")
+//!     .user("Hello!")
+//!     .assistant("Hi! How can I help you?")
+//!     .user("What's the weather like?")
+//!     .build()
+//!     .unwrap();
+//!
+//! let request = RequestBody {
+//!     messages,
+//!     model: "deepseek-chat".to_string(),
+//!     ..Default::default()
+//! };
+//! ```
+
+use std::collections::BTreeSet;
+
+use super::request::{AssistantToolCall, Content, Message};
+use crate::errors::OapiError;
+
+/// How strictly a [`Conversation`] checks the message order.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Strictness {
+    /// Every rule is checked:
+    /// - system and developer messages come before all other messages;
+    /// - user and assistant messages alternate;
+    /// - every tool message answers a pending tool call of the preceding
+    ///   assistant message, by its `tool_call_id`;
+    /// - every tool call is answered before the next user or assistant message,
+    ///   and before the end of the conversation.
+    #[default]
+    Strict,
+    /// Only the rule every provider enforces is checked: a tool message follows
+    /// an assistant message with tool calls, or another tool message.
+    Lenient,
+}
+
+/// A list of messages whose order is checked by [`Conversation::build`].
+#[derive(Debug, Default, Clone)]
+pub struct Conversation {
+    messages: Vec<Message>,
+    strictness: Strictness,
+}
+
+impl Conversation {
+    /// Creates an empty conversation with [`Strictness::Strict`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_strictness(mut self, strictness: Strictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
+
+    /// Appends any message.
+    pub fn message(mut self, message: Message) -> Self {
+        self.messages.push(message);
+        self
+    }
+
+    pub fn system(self, content: impl Into<String>) -> Self {
+        self.message(Message::system(content))
+    }
+
+    pub fn developer(self, content: impl Into<String>) -> Self {
+        self.message(Message::developer(content))
+    }
+
+    pub fn user(self, content: impl Into<Content>) -> Self {
+        self.message(Message::user(content))
+    }
+
+    /// Appends an assistant message with text content only.
+    pub fn assistant(self, content: impl Into<String>) -> Self {
+        self.message(Message::assistant(content))
+    }
+
+    pub fn tool(self, tool_call_id: impl Into<String>, content: impl Into<Content>) -> Self {
+        self.message(Message::Tool {
+            content: content.into(),
+            tool_call_id: tool_call_id.into(),
+        })
+    }
+
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    /// Checks the message order, returning an
+    /// [`OapiError::InvalidConversation`] for the first message breaking a rule.
+    pub fn validate(&self) -> Result<(), OapiError> {
+        let strict = self.strictness == Strictness::Strict;
+        let error = |index: usize, reason: String| OapiError::InvalidConversation { index, reason };
+
+        let mut seen_other = false;
+        let mut last_speaker: Option<&str> = None;
+        // The tool calls of the last assistant message, while tool messages may
+        // still follow it.
+        let mut pending: Option<BTreeSet<&str>> = None;
+
+        for (index, message) in self.messages.iter().enumerate() {
+            if let Message::Tool { tool_call_id, .. } = message {
+                let Some(calls) = pending.as_mut() else {
+                    return Err(error(
+                        index,
+                        "a tool message must follow an assistant message with tool calls"
+                            .to_string(),
+                    ));
+                };
+                if strict && !calls.remove(tool_call_id.as_str()) {
+                    return Err(error(
+                        index,
+                        format!(
+                            "tool_call_id `{}` answers no pending tool call",
+                            tool_call_id
+                        ),
+                    ));
+                }
+                // The assistant speaks again after the tool results.
+                last_speaker = Some("tool");
+                continue;
+            }
+
+            if strict
+                && let Some(calls) = &pending
+                && let Some(id) = calls.first()
+            {
+                return Err(error(
+                    index,
+                    format!("tool call `{}` was not answered by a tool message", id),
+                ));
+            }
+            pending = None;
+
+            match message {
+                Message::System { .. } | Message::Developer { .. } => {
+                    if strict && seen_other {
+                        return Err(error(
+                            index,
+                            "system and developer messages must come before the other messages"
+                                .to_string(),
+                        ));
+                    }
+                }
+                Message::User { .. } | Message::Assistant { .. } => {
+                    seen_other = true;
+                    let speaker = if matches!(message, Message::User { .. }) {
+                        "user"
+                    } else {
+                        "assistant"
+                    };
+                    if strict && last_speaker == Some(speaker) {
+                        return Err(error(
+                            index,
+                            format!(
+                                "two {} messages in a row; user and assistant must alternate",
+                                speaker
+                            ),
+                        ));
+                    }
+                    last_speaker = Some(speaker);
+
+                    if let Message::Assistant {
+                        tool_calls: Some(tool_calls),
+                        ..
+                    } = message
+                        && !tool_calls.is_empty()
+                    {
+                        pending = Some(tool_calls.iter().map(AssistantToolCall::id).collect());
+                    }
+                }
+                Message::Function { .. } | Message::Tool { .. } => seen_other = true,
+            }
+        }
+
+        if strict
+            && let Some(calls) = &pending
+            && let Some(id) = calls.first()
+        {
+            return Err(error(
+                self.messages.len(),
+                format!("tool call `{}` was not answered by a tool message", id),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks the message order and returns the messages.
+    pub fn build(self) -> Result<Vec<Message>, OapiError> {
+        self.validate()?;
+        Ok(self.messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat::request::ToolCallFunction;
+
+    fn tool_calls(ids: &[&str]) -> Message {
+        Message::Assistant {
+            content: None,
+            refusal: None,
+            name: None,
+            prefix: false,
+            reasoning_content: None,
+            tool_calls: Some(
+                ids.iter()
+                    .map(|id| AssistantToolCall::Function {
+                        id: id.to_string(),
+                        function: ToolCallFunction {
+                            arguments: "{}".to_string(),
+                            name: "get_weather".to_string(),
+                        },
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    fn invalid_at(conversation: Conversation) -> usize {
+        match conversation.build() {
+            Err(OapiError::InvalidConversation { index, .. }) => index,
+            other => panic!("expected an invalid conversation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn valid_sequences() {
+        let messages = Conversation::new()
+            .system("Be brief.")
+            .developer("Use metric units.")
+            .user("Weather in Hangzhou and Beijing?")
+            .message(tool_calls(&["call_0", "call_1"]))
+            .tool("call_1", "12°C")
+            .tool("call_0", "25°C")
+            .assistant("Hangzhou is 25°C, Beijing is 12°C.")
+            .user("Thanks!")
+            .build()
+            .unwrap();
+        assert_eq!(messages.len(), 8);
+
+        let lenient = Conversation::new()
+            .with_strictness(Strictness::Lenient)
+            .user("Hello")
+            .user("Are you there?")
+            .system("Be brief.")
+            .message(tool_calls(&["call_0"]))
+            .tool("call_9", "unknown call")
+            .user("Never mind");
+        assert!(lenient.validate().is_ok());
+    }
+
+    #[test]
+    fn invalid_sequences() {
+        assert_eq!(
+            invalid_at(Conversation::new().user("Hi").system("Be brief.")),
+            1
+        );
+        assert_eq!(invalid_at(Conversation::new().user("Hi").user("Hello?")), 1);
+        assert_eq!(
+            invalid_at(Conversation::new().user("Hi").tool("call_0", "25°C")),
+            1
+        );
+        assert_eq!(
+            invalid_at(
+                Conversation::new()
+                    .user("Weather?")
+                    .message(tool_calls(&["call_0"]))
+                    .tool("call_1", "25°C")
+            ),
+            2
+        );
+        assert_eq!(
+            invalid_at(
+                Conversation::new()
+                    .user("Weather?")
+                    .message(tool_calls(&["call_0", "call_1"]))
+                    .tool("call_0", "25°C")
+                    .assistant("It is 25°C.")
+            ),
+            3
+        );
+        assert_eq!(
+            invalid_at(
+                Conversation::new()
+                    .user("Weather?")
+                    .message(tool_calls(&["call_0"]))
+            ),
+            2
+        );
+
+        // A tool message without a tool call is rejected even when lenient.
+        let lenient = Conversation::new()
+            .with_strictness(Strictness::Lenient)
+            .user("Hi")
+            .assistant("Hello!")
+            .tool("call_0", "25°C");
+        assert_eq!(invalid_at(lenient), 2);
+    }
+}
@@ -0,0 +1,324 @@
+//! Checking that a chat request reproduces the same completion.
+//!
+//! With a fixed `seed`, providers make a best effort to sample
+//! deterministically, which is useful for regression tests of prompts.
+//! [`DeterminismCheck`] compares the completions of repeated runs, and
+//! [`assert_reproducible`] sends a request several times through a [`Client`]
+//! and returns a diff when the runs disagree.
+//!
+//! Determinism is not guaranteed by any provider. Backend changes, reported
+//! through `system_fingerprint`, and load balancing across hardware can change
+//! the output of the same request with the same seed. Use these checks to
+//! notice such changes, not to rely on reproducibility in production.
+//!
+//! To only check whether two completions were served by the same backend
+//! configuration, compare their fingerprints with
+//! [`ChatCompletion::same_backend_as`].
+
+use std::fmt;
+
+use super::request::RequestBody;
+use super::response::no_streaming::ChatCompletion;
+use crate::errors::OapiError;
+use crate::rest::Client;
+
+/// The `system_fingerprint` of a completion, identifying the backend
+/// configuration that served it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SystemFingerprint(pub String);
+
+impl SystemFingerprint {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for SystemFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl ChatCompletion {
+    /// The `system_fingerprint` of this completion, if the provider sent one.
+    pub fn fingerprint(&self) -> Option<SystemFingerprint> {
+        self.system_fingerprint.clone().map(SystemFingerprint)
+    }
+
+    /// Whether both completions report the same `system_fingerprint`.
+    ///
+    /// Returns `false` when either fingerprint is missing, since the backends
+    /// cannot be compared then.
+    pub fn same_backend_as(&self, other: &ChatCompletion) -> bool {
+        match (&self.system_fingerprint, &other.system_fingerprint) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// The parts of completions that must match across runs: the
+/// `system_fingerprint` and the content of every choice.
+#[derive(Debug, Default, Clone)]
+pub struct DeterminismCheck {
+    runs: Vec<Run>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Run {
+    system_fingerprint: Option<String>,
+    /// The content of each choice, ordered by choice index.
+    contents: Vec<Option<String>>,
+}
+
+impl DeterminismCheck {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the completion of one run.
+    pub fn push(&mut self, completion: &ChatCompletion) {
+        self.runs.push(Run {
+            system_fingerprint: completion.system_fingerprint.clone(),
+            contents: completion
+                .sorted_choices()
+                .into_iter()
+                .map(|choice| choice.message.content.clone())
+                .collect(),
+        });
+    }
+
+    /// The number of recorded runs.
+    pub fn runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Whether every run matches the first one.
+    pub fn is_reproducible(&self) -> bool {
+        self.diff().is_none()
+    }
+
+    /// Describes how each run differs from the first one, or returns `None`
+    /// if they all match.
+    pub fn diff(&self) -> Option<String> {
+        let (first, rest) = self.runs.split_first()?;
+        let mut lines = Vec::new();
+
+        for (i, run) in rest.iter().enumerate() {
+            let run_number = i + 2;
+            if run.system_fingerprint != first.system_fingerprint {
+                lines.push(format!(
+                    "run {}: system_fingerprint {:?} differs from {:?} of run 1",
+                    run_number, run.system_fingerprint, first.system_fingerprint
+                ));
+            }
+            if run.contents.len() != first.contents.len() {
+                lines.push(format!(
+                    "run {}: {} choices, run 1 has {}",
+                    run_number,
+                    run.contents.len(),
+                    first.contents.len()
+                ));
+            }
+            for (index, (expected, actual)) in first.contents.iter().zip(&run.contents).enumerate()
+            {
+                if expected != actual {
+                    lines.push(format!(
+                        "run {}, choice {}: {}",
+                        run_number,
+                        index,
+                        content_diff(expected.as_deref(), actual.as_deref())
+                    ));
+                }
+            }
+        }
+
+        if lines.is_empty() {
+            None
+        } else {
+            Some(lines.join("\n"))
+        }
+    }
+}
+
+/// Shows where two contents start to differ, with some context around.
+fn content_diff(expected: Option<&str>, actual: Option<&str>) -> String {
+    let (Some(expected), Some(actual)) = (expected, actual) else {
+        return format!("content {:?} differs from {:?} of run 1", actual, expected);
+    };
+
+    const CONTEXT: usize = 20;
+    let offset = expected
+        .chars()
+        .zip(actual.chars())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let excerpt = |text: &str| -> String {
+        text.chars()
+            .skip(offset.saturating_sub(CONTEXT))
+            .take(2 * CONTEXT)
+            .collect()
+    };
+    format!(
+        "content differs at character {}\n  run 1: {:?}\n  this:  {:?}",
+        offset,
+        excerpt(expected),
+        excerpt(actual)
+    )
+}
+
+/// Sends `request` to `/chat/completions` `n_runs` times and checks that every
+/// completion has the same content and `system_fingerprint`.
+///
+/// Runs that disagree fail with [`OapiError::NotReproducible`], holding the
+/// [`DeterminismCheck::diff`]; unwrap the result in a test to fail it with the
+/// diff. Set `seed` on the request; without it, providers sample differently
+/// on every run. A streaming request or fewer than two runs fail with
+/// [`OapiError::InvalidParameter`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use openai_interface::chat::determinism::assert_reproducible;
+/// use openai_interface::chat::request::{Message, RequestBody};
+/// use openai_interface::rest::Client;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = Client::new("https://api.openai.com/v1", "sk-...");
+///     let request = RequestBody {
+///         messages: vec![Message::User {
+///             content: "Name a prime number.".into(),
+///             name: None,
+///         }],
+///         model: "gpt-4o-mini".to_string(),
+///         seed: Some(42),
+///         temperature: Some(0.0),
+///         ..Default::default()
+///     };
+///
+///     assert_reproducible(&client, &request, 3).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn assert_reproducible(
+    client: &Client,
+    request: &RequestBody,
+    n_runs: usize,
+) -> Result<DeterminismCheck, OapiError> {
+    if n_runs < 2 {
+        return Err(OapiError::InvalidParameter(format!(
+            "n_runs must be at least 2, got {}",
+            n_runs
+        )));
+    }
+    if request.stream {
+        return Err(OapiError::InvalidParameter(
+            "assert_reproducible needs a non-streaming request".to_string(),
+        ));
+    }
+
+    let mut check = DeterminismCheck::new();
+    for _ in 0..n_runs {
+        let completion = client.post_no_stream("/chat/completions", request).await?;
+        check.push(&completion);
+    }
+
+    match check.diff() {
+        Some(diff) => Err(OapiError::NotReproducible { runs: n_runs, diff }),
+        None => Ok(check),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::test_support::{MockResponse, MockServer};
+
+    fn completion(fingerprint: &str, content: &str) -> String {
+        format!(
+            r#"{{"id":"1","object":"chat.completion","created":1,"model":"gpt-4o-mini","system_fingerprint":"{}","choices":[{{"index":0,"message":{{"role":"assistant","content":"{}"}},"finish_reason":"stop"}}]}}"#,
+            fingerprint, content
+        )
+    }
+
+    #[tokio::test]
+    async fn reproducible_runs() {
+        let server =
+            MockServer::start(vec![MockResponse::json(200, &completion("fp_1", "7"))]).await;
+        let client = Client::new(server.url(""), "key");
+        let request = RequestBody {
+            model: "gpt-4o-mini".to_string(),
+            seed: Some(42),
+            ..Default::default()
+        };
+
+        let check = assert_reproducible(&client, &request, 3).await.unwrap();
+        assert_eq!(check.runs(), 3);
+        assert_eq!(server.requests().len(), 3);
+        assert!(matches!(
+            assert_reproducible(&client, &request, 1).await,
+            Err(OapiError::InvalidParameter(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn unreproducible_runs() {
+        let server = MockServer::start(vec![
+            MockResponse::json(200, &completion("fp_1", "The answer is 7.")),
+            MockResponse::json(200, &completion("fp_1", "The answer is 7.")),
+            MockResponse::json(200, &completion("fp_2", "The answer is 11.")),
+        ])
+        .await;
+        let client = Client::new(server.url(""), "key");
+        let request = RequestBody {
+            model: "gpt-4o-mini".to_string(),
+            seed: Some(42),
+            ..Default::default()
+        };
+
+        match assert_reproducible(&client, &request, 3).await {
+            Err(OapiError::NotReproducible { runs, diff }) => {
+                assert_eq!(runs, 3);
+                assert!(diff.contains(r#"run 3: system_fingerprint Some("fp_2") differs"#));
+                assert!(diff.contains("run 3, choice 0: content differs at character 14"));
+            }
+            other => panic!("expected a diff, got {:?}", other),
+        }
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[test]
+    fn mismatch_diff() {
+        let mut check = DeterminismCheck::new();
+        check.push(&ChatCompletion::from_str(&completion("fp_1", "The answer is 7.")).unwrap());
+        check.push(&ChatCompletion::from_str(&completion("fp_1", "The answer is 7.")).unwrap());
+        assert!(check.is_reproducible());
+
+        check.push(&ChatCompletion::from_str(&completion("fp_2", "The answer is 11.")).unwrap());
+        assert!(!check.is_reproducible());
+        let diff = check.diff().unwrap();
+        assert!(diff.contains(r#"run 3: system_fingerprint Some("fp_2") differs"#));
+        assert!(diff.contains("run 3, choice 0: content differs at character 14"));
+    }
+
+    #[test]
+    fn compare_fingerprints() {
+        let first = ChatCompletion::from_str(&completion("fp_1", "7")).unwrap();
+        let same = ChatCompletion::from_str(&completion("fp_1", "11")).unwrap();
+        let other = ChatCompletion::from_str(&completion("fp_2", "7")).unwrap();
+        assert!(first.same_backend_as(&same));
+        assert!(!first.same_backend_as(&other));
+        assert_eq!(
+            first.fingerprint(),
+            Some(SystemFingerprint("fp_1".to_string()))
+        );
+
+        let mut missing = ChatCompletion::from_str(&completion("fp_1", "7")).unwrap();
+        missing.system_fingerprint = None;
+        assert!(!missing.same_backend_as(&missing));
+        assert_eq!(missing.fingerprint(), None);
+    }
+}
@@ -1,4 +1,9 @@
 //! Response to a given `chat` conversation.
 
+pub mod conversation;
+pub mod determinism;
+pub mod model_limits;
 pub mod request;
 pub mod response;
+#[cfg(feature = "tokenizer")]
+pub mod tokens;
@@ -0,0 +1,78 @@
+//! Context and output limits of well-known chat models.
+//!
+//! [`RequestBody::validate`](super::request::RequestBody::validate) uses these
+//! to reject a `max_completion_tokens` or `max_tokens` the model can never
+//! produce. Models missing here are not checked; pass your own limits to
+//! [`RequestBody::validate_with_limits`](super::request::RequestBody::validate_with_limits)
+//! for those.
+
+/// Token limits of a model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelLimits {
+    /// The maximum number of tokens of the prompt and the completion together.
+    pub context_window: u32,
+    /// The maximum number of tokens the model can generate in one completion.
+    pub max_output_tokens: u32,
+}
+
+/// Known models and their limits. Dated snapshots such as `gpt-4o-2024-08-06`
+/// are matched by the longest known prefix.
+const KNOWN_LIMITS: &[(&str, ModelLimits)] = &[
+    ("deepseek-chat", limits(131_072, 8_192)),
+    ("deepseek-reasoner", limits(131_072, 65_536)),
+    ("gpt-3.5-turbo", limits(16_385, 4_096)),
+    ("gpt-4", limits(8_192, 8_192)),
+    ("gpt-4-turbo", limits(128_000, 4_096)),
+    ("gpt-4o", limits(128_000, 16_384)),
+    ("gpt-4o-mini", limits(128_000, 16_384)),
+    ("gpt-4.1", limits(1_047_576, 32_768)),
+    ("o1", limits(200_000, 100_000)),
+    ("o3", limits(200_000, 100_000)),
+    ("o3-mini", limits(200_000, 100_000)),
+    ("o4-mini", limits(200_000, 100_000)),
+];
+
+const fn limits(context_window: u32, max_output_tokens: u32) -> ModelLimits {
+    ModelLimits {
+        context_window,
+        max_output_tokens,
+    }
+}
+
+/// Looks up the limits of a known model.
+pub fn known_limits(model: &str) -> Option<ModelLimits> {
+    KNOWN_LIMITS
+        .iter()
+        .filter(|(name, _)| {
+            model == *name
+                || model
+                    .strip_prefix(name)
+                    .is_some_and(|rest| rest.starts_with('-'))
+        })
+        .max_by_key(|(name, _)| name.len())
+        .map(|(_, limits)| *limits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        assert_eq!(
+            known_limits("deepseek-chat").map(|l| l.max_output_tokens),
+            Some(8_192)
+        );
+        assert_eq!(
+            known_limits("gpt-4o-mini-2024-07-18").map(|l| l.max_output_tokens),
+            Some(16_384)
+        );
+        assert_eq!(
+            known_limits("gpt-4-turbo-preview").map(|l| l.max_output_tokens),
+            Some(4_096)
+        );
+        assert_eq!(known_limits("gpt-4o-mini"), known_limits("gpt-4o"));
+        assert_eq!(known_limits("o1x"), None);
+        assert_eq!(known_limits("qwen-plus"), None);
+    }
+}
@@ -1,7 +1,20 @@
 //! This module contains the request body and POST method for the chat completion API.
 
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use base64::Engine;
+use futures_util::{StreamExt, stream::BoxStream};
 use serde::Serialize;
 
+use super::model_limits::{ModelLimits, known_limits};
+use super::response::no_streaming::{
+    ChatCompletion, ChatCompletionMessage, ChatCompletionMessageToolCall,
+};
+use super::response::streaming::text_stream;
+use crate::errors::OapiError;
 use crate::rest::post::{NoStream, Post, Stream};
 
 /// Creates a model response for the given chat conversation.
@@ -16,8 +29,8 @@ use crate::rest::post::{NoStream, Post, Stream};
 ///
 /// const DEEPSEEK_API_KEY: LazyLock<&str> =
 ///     LazyLock::new(|| include_str!("../.././keys/deepseek_domestic_key").trim());
-/// const DEEPSEEK_CHAT_URL: &'static str = "https://api.deepseek.com/chat/completions";
-/// const DEEPSEEK_MODEL: &'static str = "deepseek-chat";
+/// const DEEPSEEK_CHAT_URL: &str = "https://api.deepseek.com/chat/completions";
+/// const DEEPSEEK_MODEL: &str = "deepseek-chat";
 ///
 /// #[tokio::main]
 /// async fn main() {
@@ -28,7 +41,7 @@ use crate::rest::post::{NoStream, Post, Stream};
 ///                 name: None,
 ///             },
 ///             Message::User {
-///                 content: "What's your name?".to_string(),
+///                 content: "What's your name?".into(),
 ///                 name: None,
 ///             },
 ///         ],
@@ -48,6 +61,7 @@ use crate::rest::post::{NoStream, Post, Stream};
 /// }
 /// ```
 #[derive(Serialize, Debug, Default, Clone)]
+#[serde(remote = "Self")]
 pub struct RequestBody {
     /// A list of messages comprising the conversation so far.
     pub messages: Vec<Message>,
@@ -71,9 +85,22 @@ pub struct RequestBody {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub presence_penalty: Option<f32>,
 
+    /// Modify the likelihood of specified tokens appearing in the completion.
+    ///
+    /// Maps token IDs to a bias value from -100 to 100, which is added to the
+    /// logits before sampling. Values like -100 or 100 should result in a ban
+    /// or exclusive selection of the token.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logit_bias: Option<HashMap<u32, i8>>,
+
     /// The maximum number of tokens that can be generated in the chat completion.
     /// Deprecated according to OpenAI's Python SDK in favour of
     /// `max_completion_tokens`.
+    ///
+    /// Set only one of the two: some providers reject requests with both, and
+    /// reasoning models reject `max_tokens`. Neither takes precedence: both are
+    /// sent as set, so [`RequestBody::validate`] returns an error when both
+    /// are set, and [`RequestBody::with_max_completion_tokens`] clears this one.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_tokens: Option<u32>,
 
@@ -113,9 +140,18 @@ pub struct RequestBody {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub n: Option<u32>,
 
-    /// Up to 4 sequences where the API will stop generating further tokens. The
-    /// returned text will not contain the stop sequence.
+    /// Configuration for Predicted Outputs, which can greatly reduce response
+    /// times when large parts of the response are known ahead of time, such as
+    /// when regenerating a file with only minor changes. The accounting shows
+    /// up in `accepted_prediction_tokens` and `rejected_prediction_tokens` of
+    /// the usage.
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub prediction: Option<Prediction>,
+
+    /// Up to 4 sequences where the API will stop generating further tokens. The
+    /// returned text will not contain the stop sequence. An empty list is not
+    /// sent.
+    #[serde(skip_serializing_if = "StopKeywords::is_unset")]
     pub stop: Option<StopKeywords>,
 
     /// Options for streaming response. Only set this when you set `stream: true`
@@ -126,6 +162,7 @@ pub struct RequestBody {
     /// make the output more random, while lower values like 0.2 will make it more
     /// focused and deterministic. It is generally recommended to alter this or `top_p` but
     /// not both.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
 
     /// An alternative to sampling with temperature, called nucleus sampling, where the
@@ -133,6 +170,7 @@ pub struct RequestBody {
     /// means only the tokens comprising the top 10% probability mass are considered.
     ///
     /// It is generally recommended to alter this or `temperature` but not both.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub top_p: Option<f32>,
 
     /// A list of tools the model may call.
@@ -148,6 +186,12 @@ pub struct RequestBody {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_choice: Option<ToolChoice>,
 
+    /// Whether to enable parallel function calling during tool use. Providers
+    /// default to `true`; set `false` to get at most one tool call per
+    /// response, e.g. when the tools must run one after another.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
+
     /// Whether to return log probabilities of the output tokens or not. If true,
     /// returns the log probabilities of each output token returned in the `content` of
     /// `message`.
@@ -165,11 +209,69 @@ pub struct RequestBody {
     pub extra_body: Option<ExtraBody>,
 
     /// Other request bodies that are not in standard OpenAI API and
-    /// not included in the ExtraBody struct.
-    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    /// not included in the ExtraBody struct. Use [`RequestBody::extra`] to add
+    /// entries one by one.
+    ///
+    /// A key that is also set by a field of the request or of `extra_body` is
+    /// not serialized: the typed field takes precedence, so the body never has
+    /// duplicate keys. Keys of fields that are `None` are free to use.
+    #[serde(skip)]
     pub extra_body_map: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
+impl Serialize for RequestBody {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        struct Fields<'a>(&'a RequestBody);
+
+        impl Serialize for Fields<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                RequestBody::serialize(self.0, serializer)
+            }
+        }
+
+        /// The entries of `extra_body_map` whose keys no field has set.
+        struct Extra<'a> {
+            map: &'a serde_json::Map<String, serde_json::Value>,
+            taken: serde_json::Map<String, serde_json::Value>,
+        }
+
+        impl Serialize for Extra<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_map(
+                    self.map
+                        .iter()
+                        .filter(|(key, _)| !self.taken.contains_key(key.as_str())),
+                )
+            }
+        }
+
+        #[derive(Serialize)]
+        struct Body<'a> {
+            #[serde(flatten)]
+            fields: Fields<'a>,
+            #[serde(flatten, skip_serializing_if = "Option::is_none")]
+            extra: Option<Extra<'a>>,
+        }
+
+        let extra = match &self.extra_body_map {
+            Some(map) if !map.is_empty() => {
+                let taken = match serde_json::to_value(Fields(self)) {
+                    Ok(serde_json::Value::Object(taken)) => taken,
+                    Ok(_) => serde_json::Map::new(),
+                    Err(e) => return Err(serde::ser::Error::custom(e)),
+                };
+                Some(Extra { map, taken })
+            }
+            _ => None,
+        };
+        Body {
+            fields: Fields(self),
+            extra,
+        }
+        .serialize(serializer)
+    }
+}
+
 #[derive(Serialize, Debug, Clone)]
 #[serde(tag = "role", rename_all = "lowercase")]
 pub enum Message {
@@ -188,8 +290,9 @@ pub enum Message {
     /// In this case, the role of the message author is `user`.
     /// The field `{ role = "user" }` is added automatically.
     User {
-        /// The contents of the user message.
-        content: String,
+        /// The contents of the user message: plain text, or text and image
+        /// parts for vision models.
+        content: Content,
         /// An optional name for the participant.
         ///
         /// Provides the model information to differentiate between
@@ -229,8 +332,9 @@ pub enum Message {
     /// In this case, the role of the message author is `assistant`.
     /// The field `{ role = "tool" }` is added automatically.
     Tool {
-        /// The contents of the tool message.
-        content: String,
+        /// The contents of the tool message, either text or, for providers
+        /// supporting rich tool results, an array of content parts.
+        content: Content,
         /// Tool call that this message is responding to.
         tool_call_id: String,
     },
@@ -251,12 +355,163 @@ pub enum Message {
         ///
         /// Provides the model information to differentiate between
         /// participants of the same role.
+        #[serde(skip_serializing_if = "Option::is_none")]
         name: Option<String>,
     },
 }
 
+impl Message {
+    /// A system message without a `name`.
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::System {
+            content: content.into(),
+            name: None,
+        }
+    }
+
+    /// A developer message without a `name`.
+    pub fn developer(content: impl Into<String>) -> Self {
+        Self::Developer {
+            content: content.into(),
+            name: None,
+        }
+    }
+
+    /// A user message without a `name`.
+    pub fn user(content: impl Into<Content>) -> Self {
+        Self::User {
+            content: content.into(),
+            name: None,
+        }
+    }
+
+    /// An assistant message with text content only: no refusal, tool calls or
+    /// reasoning, and not a prefix.
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self::Assistant {
+            content: Some(content.into()),
+            refusal: None,
+            name: None,
+            prefix: false,
+            reasoning_content: None,
+            tool_calls: None,
+        }
+    }
+
+    /// A tool message answering `tool_call`, linked to it by its ID. See
+    /// [`RequestBody::with_assistant_message`] for an example.
+    pub fn tool_result(
+        tool_call: &ChatCompletionMessageToolCall,
+        result: impl Into<Content>,
+    ) -> Self {
+        Self::Tool {
+            content: result.into(),
+            tool_call_id: tool_call.id().to_string(),
+        }
+    }
+}
+
+/// The contents of a message: plain text, or an array of content parts.
+///
+/// Strings convert into [`Content::Text`], so `"text".into()` is enough for the
+/// common case.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Content {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+impl From<String> for Content {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&str> for Content {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+impl From<Vec<ContentPart>> for Content {
+    fn from(parts: Vec<ContentPart>) -> Self {
+        Self::Parts(parts)
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    /// A text part.
+    Text { text: String },
+    /// An image part, given by URL or as a base64 data URL.
+    ImageUrl { image_url: ImageUrl },
+}
+
+impl ContentPart {
+    /// Reads a local image into an image part with a base64 `data:` URL.
+    ///
+    /// The MIME type is taken from the file extension. PNG, JPEG, GIF and WebP
+    /// images are supported, as accepted by vision models; other extensions
+    /// return an [`OapiError::InvalidParameter`].
+    pub fn image_from_path(path: impl AsRef<Path>) -> Result<ContentPart, OapiError> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+        let mime_type = match extension.as_deref() {
+            Some("png") => "image/png",
+            Some("jpg" | "jpeg") => "image/jpeg",
+            Some("gif") => "image/gif",
+            Some("webp") => "image/webp",
+            _ => {
+                return Err(OapiError::InvalidParameter(format!(
+                    "unsupported image type of {}; expected png, jpg, jpeg, gif or webp",
+                    path.display()
+                )));
+            }
+        };
+
+        if !path.exists() {
+            return Err(OapiError::FileNotFoundError(path.to_path_buf()));
+        }
+        let data = std::fs::read(path).map_err(OapiError::FileReadError)?;
+
+        Ok(ContentPart::ImageUrl {
+            image_url: ImageUrl {
+                url: format!(
+                    "data:{};base64,{}",
+                    mime_type,
+                    base64::engine::general_purpose::STANDARD.encode(data)
+                ),
+                detail: None,
+            },
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ImageUrl {
+    /// Either a URL of the image or the base64 encoded image data, as
+    /// `data:image/png;base64,...`.
+    pub url: String,
+    /// Specifies the detail level of the image.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<ImageDetail>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageDetail {
+    Auto,
+    Low,
+    High,
+}
+
 #[derive(Debug, Serialize, Clone)]
-#[serde(tag = "role", rename_all = "lowercase")]
+#[serde(tag = "type", rename_all = "lowercase")]
 pub enum AssistantToolCall {
     Function {
         /// The ID of the tool call.
@@ -272,23 +527,33 @@ pub enum AssistantToolCall {
     },
 }
 
+impl AssistantToolCall {
+    /// The ID of the tool call, as answered by the `tool_call_id` of a tool
+    /// message.
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Function { id, .. } | Self::Custom { id, .. } => id,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct ToolCallFunction {
     /// The arguments to call the function with, as generated by the model in JSON
     /// format. Note that the model does not always generate valid JSON, and may
     /// hallucinate parameters not defined by your function schema. Validate the
     /// arguments in your code before calling your function.
-    arguments: String,
+    pub arguments: String,
     /// The name of the function to call.
-    name: String,
+    pub name: String,
 }
 
 #[derive(Debug, Serialize, Clone)]
 pub struct ToolCallCustom {
     /// The input for the custom tool call generated by the model.
-    input: String,
+    pub input: String,
     /// The name of the custom tool to call.
-    name: String,
+    pub name: String,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -324,11 +589,54 @@ pub struct JSONSchema {
     pub strict: Option<bool>,
 }
 
+impl JSONSchema {
+    /// Creates a JSON schema response format from a `serde_json::Value`,
+    /// such as one built with `serde_json::json!`.
+    ///
+    /// Returns an [`OapiError::InvalidParameter`] if `schema` is not a JSON
+    /// object, or if `name` is empty, longer than 64 characters, or contains
+    /// characters other than a-z, A-Z, 0-9, underscores and dashes. Providers
+    /// reject such formats, but only once the request is sent.
+    pub fn from_value(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        schema: serde_json::Value,
+        strict: Option<bool>,
+    ) -> Result<Self, OapiError> {
+        let name = name.into();
+        if name.is_empty()
+            || name.len() > 64
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(OapiError::InvalidParameter(format!(
+                "invalid JSON schema name `{}`; expected 1 to 64 characters of a-z, A-Z, 0-9, `_` or `-`",
+                name
+            )));
+        }
+        let serde_json::Value::Object(schema) = schema else {
+            return Err(OapiError::InvalidParameter(format!(
+                "the JSON schema `{}` must be a JSON object",
+                name
+            )));
+        };
+
+        Ok(Self {
+            name,
+            description: description.into(),
+            schema,
+            strict,
+        })
+    }
+}
+
 #[inline]
 fn is_false(value: &bool) -> bool {
     !value
 }
 
+/// The `stop` parameter of chat and legacy completion requests.
 #[derive(Serialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum StopKeywords {
@@ -336,8 +644,48 @@ pub enum StopKeywords {
     Words(Vec<String>),
 }
 
-#[derive(Serialize, Debug, Clone)]
+impl StopKeywords {
+    /// Whether `stop` is `None` or an empty list, which providers either
+    /// reject or ignore, so the parameter is left out of the request.
+    pub(crate) fn is_unset(stop: &Option<StopKeywords>) -> bool {
+        match stop {
+            None => true,
+            Some(StopKeywords::Words(words)) => words.is_empty(),
+            Some(StopKeywords::Word(_)) => false,
+        }
+    }
+}
+
+/// Static predicted output content, such as the content of a file being
+/// regenerated.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Prediction {
+    /// The content that should be matched when generating a response, as text
+    /// or as text parts.
+    Content { content: Content },
+}
+
+impl Prediction {
+    pub fn content(content: impl Into<Content>) -> Self {
+        Self::Content {
+            content: content.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
 pub struct StreamOptions {
+    /// When true, stream obfuscation will be enabled.
+    ///
+    /// Stream obfuscation adds random characters to an `obfuscation` field on streaming
+    /// delta events to normalize payload sizes as a mitigation to certain side-channel
+    /// attacks. These obfuscation fields are included by default, but add a small
+    /// amount of overhead to the data stream. Set this to `false` to optimize for
+    /// bandwidth if you trust the network links between your application and the
+    /// provider. Leave it `None` for providers that do not know the option.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_obfuscation: Option<bool>,
     /// If set, an additional chunk will be streamed before the `data: [DONE]` message.
     ///
     /// The `usage` field on this chunk shows the token usage statistics for the entire
@@ -456,7 +804,7 @@ pub struct ToolChoiceAllowedTools {
     /// Constrains the tools available to the model to a pre-defined set.
     ///
     /// - `auto` allows the model to pick from among the allowed tools and generate a
-    /// message.
+    ///   message.
     /// - `required` requires the model to call one or more of the allowed tools.
     pub mode: ToolChoiceAllowedToolsMode,
     /// A list of tool definitions that the model should be allowed to call.
@@ -515,10 +863,284 @@ pub struct ExtraBody {
     pub top_k: Option<u32>,
 }
 
+impl RequestBody {
+    /// Checks the request locally for parameters the provider would reject.
+    ///
+    /// - `max_completion_tokens` and `max_tokens` are checked against the output
+    ///   limit of the model if it is one of the [known models](known_limits).
+    /// - The `tool_call_id` of every tool message must be the ID of a tool call
+    ///   in a preceding assistant message.
+    /// - `temperature` must be in [0, 2], `top_p` in [0, 1],
+    ///   `frequency_penalty` and `presence_penalty` in [-2, 2], and
+    ///   `top_logprobs` in [0, 20].
+    /// - Every `logit_bias` value must be in [-100, 100].
+    /// - `max_tokens` and `max_completion_tokens` are not both set.
+    pub fn validate(&self) -> Result<(), OapiError> {
+        match known_limits(&self.model) {
+            Some(limits) => self.validate_with_limits(limits),
+            None => {
+                self.validate_parameters()?;
+                self.validate_tool_call_ids()
+            }
+        }
+    }
+
+    /// Same as [`RequestBody::validate`], but checks against the given limits
+    /// instead of the known ones. Useful for models the crate does not know.
+    pub fn validate_with_limits(&self, limits: ModelLimits) -> Result<(), OapiError> {
+        self.validate_parameters()?;
+        self.validate_tool_call_ids()?;
+        for (name, value) in [
+            ("max_completion_tokens", self.max_completion_tokens),
+            ("max_tokens", self.max_tokens),
+        ] {
+            if let Some(value) = value
+                && value > limits.max_output_tokens
+            {
+                return Err(OapiError::InvalidParameter(format!(
+                    "`{}` is {}, but model `{}` generates at most {} tokens",
+                    name, value, self.model, limits.max_output_tokens
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Rejects sampling parameters outside of the ranges the API accepts, and
+    /// both token limits set at once.
+    fn validate_parameters(&self) -> Result<(), OapiError> {
+        if self.max_tokens.is_some() && self.max_completion_tokens.is_some() {
+            return Err(OapiError::InvalidParameter(
+                "`max_tokens` and `max_completion_tokens` are both set; set only \
+                 `max_completion_tokens`"
+                    .to_string(),
+            ));
+        }
+
+        let top_logprobs = self.top_logprobs.map(|value| value as f32);
+        for (name, value, min, max) in [
+            ("temperature", self.temperature, 0.0, 2.0),
+            ("top_p", self.top_p, 0.0, 1.0),
+            ("frequency_penalty", self.frequency_penalty, -2.0, 2.0),
+            ("presence_penalty", self.presence_penalty, -2.0, 2.0),
+            ("top_logprobs", top_logprobs, 0.0, 20.0),
+        ] {
+            if let Some(value) = value
+                && !(min..=max).contains(&value)
+            {
+                return Err(OapiError::InvalidParameter(format!(
+                    "`{}` is {}, but must be between {} and {}",
+                    name, value, min, max
+                )));
+            }
+        }
+
+        if let Some(logit_bias) = &self.logit_bias
+            && let Some((token, bias)) = logit_bias
+                .iter()
+                .filter(|(_, bias)| !(-100..=100).contains(*bias))
+                .min()
+        {
+            return Err(OapiError::InvalidParameter(format!(
+                "`logit_bias` of token {} is {}, but must be between -100 and 100",
+                token, bias
+            )));
+        }
+        Ok(())
+    }
+
+    /// Rejects tool messages answering no tool call of an earlier assistant
+    /// message, listing all of their IDs.
+    fn validate_tool_call_ids(&self) -> Result<(), OapiError> {
+        let mut called = HashSet::new();
+        let mut orphaned: Vec<&str> = Vec::new();
+        for message in &self.messages {
+            match message {
+                Message::Assistant {
+                    tool_calls: Some(tool_calls),
+                    ..
+                } => called.extend(tool_calls.iter().map(AssistantToolCall::id)),
+                Message::Tool { tool_call_id, .. }
+                    if !called.contains(tool_call_id.as_str())
+                        && !orphaned.contains(&tool_call_id.as_str()) =>
+                {
+                    orphaned.push(tool_call_id);
+                }
+                _ => {}
+            }
+        }
+
+        if orphaned.is_empty() {
+            return Ok(());
+        }
+        let ids: Vec<String> = orphaned.iter().map(|id| format!("`{}`", id)).collect();
+        Err(OapiError::InvalidParameter(format!(
+            "tool messages answer no preceding assistant tool call: {}",
+            ids.join(", ")
+        )))
+    }
+
+    /// Appends the assistant message of `completion` to the conversation, so
+    /// the request can be sent again to continue it.
+    ///
+    /// The message of the choice with index 0 is used, with its tool calls and
+    /// reasoning content. A completion without choices leaves the request
+    /// unchanged.
+    pub fn into_next_request(mut self, completion: &ChatCompletion) -> Self {
+        if let Some(choice) = completion
+            .choice_by_index(0)
+            .or_else(|| completion.choices.first())
+        {
+            self.messages.push(choice.message.to_request_message());
+        }
+        self
+    }
+
+    /// Appends `message`, an assistant message of a response, to the
+    /// conversation with its tool calls and reasoning content, ready for the
+    /// results of the tool calls.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use openai_interface::chat::request::{Message, RequestBody};
+    /// use openai_interface::chat::response::no_streaming::ChatCompletion;
+    ///
+    /// let completion: ChatCompletion = r#"{"id":"1","object":"chat.completion","created":1,"model":"deepseek-chat","choices":[{"index":0,"message":{"role":"assistant","content":"","tool_calls":[{"id":"call_0","type":"function","function":{"name":"get_weather","arguments":"{\"city\":\"Hangzhou\"}"}}]},"finish_reason":"tool_calls"}]}"#
+    ///     .parse()
+    ///     .unwrap();
+    /// let message = &completion.choices[0].message;
+    ///
+    /// let mut request = RequestBody {
+    ///     messages: vec![Message::user("How is the weather in Hangzhou?")],
+    ///     model: "deepseek-chat".to_string(),
+    ///     ..Default::default()
+    /// }
+    /// .with_assistant_message(message);
+    /// for tool_call in message.tool_calls.iter().flatten() {
+    ///     request.messages.push(Message::tool_result(tool_call, "It is sunny"));
+    /// }
+    ///
+    /// assert!(matches!(
+    ///     &request.messages[2],
+    ///     Message::Tool { tool_call_id, .. } if tool_call_id == "call_0"
+    /// ));
+    /// ```
+    pub fn with_assistant_message(mut self, message: &ChatCompletionMessage) -> Self {
+        self.messages.push(message.to_request_message());
+        self
+    }
+
+    /// Appends the result of a tool call as a tool message.
+    ///
+    /// # Example
+    ///
+    /// A conversation with one tool: the model calls it, the tool runs, and the
+    /// result is sent back for the final answer.
+    ///
+    /// ```rust,no_run
+    /// use openai_interface::chat::request::{Message, RequestBody, RequestTool, ToolFunction};
+    /// use openai_interface::chat::response::no_streaming::ChatCompletionMessageToolCall;
+    /// use openai_interface::rest::post::NoStream;
+    ///
+    /// const DEEPSEEK_CHAT_URL: &str = "https://api.deepseek.com/chat/completions";
+    ///
+    /// fn get_weather(arguments: &str) -> String {
+    ///     format!("It is sunny in {}", arguments)
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let key = "sk-...";
+    ///     let mut request = RequestBody {
+    ///         messages: vec![Message::User {
+    ///             content: "How is the weather in Hangzhou?".into(),
+    ///             name: None,
+    ///         }],
+    ///         model: "deepseek-chat".to_string(),
+    ///         tools: Some(vec![RequestTool::Function {
+    ///             function: ToolFunction {
+    ///                 name: "get_weather".to_string(),
+    ///                 description: "Get the weather of a city".to_string(),
+    ///                 parameters: serde_json::json!({
+    ///                     "type": "object",
+    ///                     "properties": { "city": { "type": "string" } },
+    ///                     "required": ["city"]
+    ///                 })
+    ///                 .as_object()
+    ///                 .unwrap()
+    ///                 .clone(),
+    ///                 strict: None,
+    ///             },
+    ///         }]),
+    ///         ..Default::default()
+    ///     };
+    ///
+    ///     let completion = request.get_response(DEEPSEEK_CHAT_URL, key).await?;
+    ///     let tool_calls = completion.choices[0].message.tool_calls.clone().unwrap_or_default();
+    ///
+    ///     request = request.into_next_request(&completion);
+    ///     for tool_call in tool_calls {
+    ///         if let ChatCompletionMessageToolCall::Function { id, function } = tool_call {
+    ///             request = request.with_tool_result(id, get_weather(&function.arguments));
+    ///         }
+    ///     }
+    ///
+    ///     let answer = request.get_response(DEEPSEEK_CHAT_URL, key).await?;
+    ///     println!("{:?}", answer.choices[0].message.content);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_tool_result(
+        mut self,
+        tool_call_id: impl Into<String>,
+        result: impl Into<Content>,
+    ) -> Self {
+        self.messages.push(Message::Tool {
+            content: result.into(),
+            tool_call_id: tool_call_id.into(),
+        });
+        self
+    }
+
+    /// Adds a top-level field to the body through
+    /// [`extra_body_map`](RequestBody::extra_body_map), creating the map if
+    /// needed. Typed fields take precedence over keys set this way.
+    pub fn extra(mut self, key: impl Into<String>, value: impl Into<serde_json::Value>) -> Self {
+        self.extra_body_map
+            .get_or_insert_with(serde_json::Map::new)
+            .insert(key.into(), value.into());
+        self
+    }
+
+    /// Sends the streaming request and yields only the answer text, see
+    /// [`text_stream`].
+    pub async fn get_text_stream(
+        &self,
+        url: &str,
+        key: &str,
+    ) -> Result<BoxStream<'static, Result<String, OapiError>>, OapiError> {
+        let chunks = self.get_stream_response(url, key).await?;
+        Ok(text_stream(chunks).boxed())
+    }
+
+    /// Sets `max_completion_tokens` and clears the deprecated `max_tokens`, so
+    /// only one limit is sent.
+    pub fn with_max_completion_tokens(mut self, max_completion_tokens: u32) -> Self {
+        self.max_completion_tokens = Some(max_completion_tokens);
+        self.max_tokens = None;
+        self
+    }
+}
+
 impl Post for RequestBody {
     fn is_streaming(&self) -> bool {
         self.stream
     }
+
+    fn path(&self) -> Option<String> {
+        Some("/chat/completions".to_string())
+    }
 }
 
 impl NoStream for RequestBody {
@@ -537,10 +1159,551 @@ mod request_test {
 
     use super::*;
 
-    const DEEPSEEK_API_KEY: LazyLock<&str> =
+    static DEEPSEEK_API_KEY: LazyLock<&str> =
         LazyLock::new(|| include_str!("../.././keys/deepseek_domestic_key").trim());
-    const DEEPSEEK_CHAT_URL: &'static str = "https://api.deepseek.com/chat/completions";
-    const DEEPSEEK_MODEL: &'static str = "deepseek-chat";
+    const DEEPSEEK_CHAT_URL: &str = "https://api.deepseek.com/chat/completions";
+    const DEEPSEEK_MODEL: &str = "deepseek-chat";
+
+    #[tokio::test]
+    async fn text_stream_skips_empty_deltas() {
+        let chunk = |delta: &str| {
+            format!(
+                r#"{{"id":"1","object":"chat.completion.chunk","created":1,"model":"deepseek-reasoner","choices":[{{"index":0,"delta":{},"finish_reason":null}}]}}"#,
+                delta
+            )
+        };
+        let server = crate::test_support::MockServer::start(vec![
+            crate::test_support::MockResponse::sse([
+                chunk(r#"{"role":"assistant","content":""}"#).as_str(),
+                chunk(r#"{"content":null,"reasoning_content":"Think."}"#).as_str(),
+                chunk(r#"{"content":"Hello"}"#).as_str(),
+                chunk(r#"{"content":", world!"}"#).as_str(),
+                r#"{"id":"1","object":"chat.completion.chunk","created":1,"model":"deepseek-reasoner","choices":[],"usage":{"prompt_tokens":1,"completion_tokens":3,"total_tokens":4}}"#,
+                "[DONE]",
+            ]),
+        ])
+        .await;
+
+        let request = RequestBody {
+            model: "deepseek-reasoner".to_string(),
+            stream: true,
+            ..Default::default()
+        };
+        let text: Vec<String> = request
+            .get_text_stream(&server.url("/chat/completions"), "key")
+            .await
+            .unwrap()
+            .map(|text| text.unwrap())
+            .collect()
+            .await;
+        assert_eq!(text, vec!["Hello", ", world!"]);
+    }
+
+    #[test]
+    fn tool_result_follows_tool_call() {
+        let completion: ChatCompletion = r#"{"id":"1","object":"chat.completion","created":1,"model":"deepseek-chat","choices":[{"index":0,"message":{"role":"assistant","content":"","tool_calls":[{"id":"call_0","type":"function","function":{"name":"get_weather","arguments":"{}"}}]},"logprobs":null,"finish_reason":"tool_calls"}]}"#
+            .parse()
+            .unwrap();
+
+        let request = RequestBody {
+            messages: vec![Message::User {
+                content: "Weather?".into(),
+                name: None,
+            }],
+            model: DEEPSEEK_MODEL.to_string(),
+            ..Default::default()
+        }
+        .into_next_request(&completion)
+        .with_tool_result("call_0", "25°C");
+
+        let value = serde_json::to_value(&request).unwrap();
+        let messages = value["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[1]["tool_calls"][0]["id"], "call_0");
+        assert_eq!(
+            messages[2],
+            serde_json::json!({ "role": "tool", "content": "25°C", "tool_call_id": "call_0" })
+        );
+    }
+
+    #[test]
+    fn tool_result_links_tool_call() {
+        let completion: ChatCompletion = r#"{"id":"1","object":"chat.completion","created":1,"model":"deepseek-chat","choices":[{"index":0,"message":{"role":"assistant","content":"","tool_calls":[{"id":"call_0","type":"function","function":{"name":"get_weather","arguments":"{}"}},{"id":"call_1","type":"custom","custom":{"name":"run","input":"ls"}}]},"logprobs":null,"finish_reason":"tool_calls"}]}"#
+            .parse()
+            .unwrap();
+        let message = &completion.choices[0].message;
+
+        let mut request = RequestBody {
+            messages: vec![Message::user("Weather?")],
+            model: DEEPSEEK_MODEL.to_string(),
+            ..Default::default()
+        }
+        .with_assistant_message(message);
+        for (tool_call, result) in message.tool_calls.iter().flatten().zip(["25°C", "a.txt"]) {
+            request
+                .messages
+                .push(Message::tool_result(tool_call, result));
+        }
+
+        let value = serde_json::to_value(&request.messages).unwrap();
+        assert_eq!(value[1]["tool_calls"][1]["id"], "call_1");
+        assert_eq!(
+            value[2],
+            serde_json::json!({ "role": "tool", "content": "25°C", "tool_call_id": "call_0" })
+        );
+        assert_eq!(
+            value[3],
+            serde_json::json!({ "role": "tool", "content": "a.txt", "tool_call_id": "call_1" })
+        );
+    }
+
+    #[test]
+    fn message_constructors() {
+        let messages = vec![
+            Message::system("Be brief."),
+            Message::developer("Use metric units."),
+            Message::user("Hi"),
+            Message::assistant("Hello!"),
+        ];
+        assert_eq!(
+            serde_json::to_value(&messages).unwrap(),
+            serde_json::json!([
+                { "role": "system", "content": "Be brief." },
+                { "role": "developer", "content": "Use metric units." },
+                { "role": "user", "content": "Hi" },
+                { "role": "assistant", "content": "Hello!" },
+            ])
+        );
+    }
+
+    #[test]
+    fn serialize_parallel_tool_calls() {
+        let request = RequestBody {
+            model: "gpt-4o".to_string(),
+            ..Default::default()
+        };
+        assert!(
+            serde_json::to_value(&request)
+                .unwrap()
+                .get("parallel_tool_calls")
+                .is_none()
+        );
+
+        let request = RequestBody {
+            parallel_tool_calls: Some(false),
+            ..request
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap()["parallel_tool_calls"],
+            false
+        );
+    }
+
+    #[test]
+    fn serialize_developer_name() {
+        let unnamed = serde_json::to_value(Message::developer("Use metric units.")).unwrap();
+        assert!(unnamed.get("name").is_none());
+
+        let named = Message::Developer {
+            content: "Use metric units.".to_string(),
+            name: Some("style_guide".to_string()),
+        };
+        assert_eq!(
+            serde_json::to_value(&named).unwrap(),
+            serde_json::json!({
+                "role": "developer",
+                "content": "Use metric units.",
+                "name": "style_guide",
+            })
+        );
+    }
+
+    #[test]
+    fn serialize_tool_content() {
+        let text = Message::Tool {
+            content: "25°C".into(),
+            tool_call_id: "call_0".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&text).unwrap(),
+            serde_json::json!({ "role": "tool", "content": "25°C", "tool_call_id": "call_0" })
+        );
+
+        let parts = Message::Tool {
+            content: vec![
+                ContentPart::Text {
+                    text: "The chart:".to_string(),
+                },
+                ContentPart::ImageUrl {
+                    image_url: ImageUrl {
+                        url: "data:image/png;base64,iVBORw0KGgo=".to_string(),
+                        detail: Some(ImageDetail::Low),
+                    },
+                },
+            ]
+            .into(),
+            tool_call_id: "call_1".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&parts).unwrap(),
+            serde_json::json!({
+                "role": "tool",
+                "content": [
+                    { "type": "text", "text": "The chart:" },
+                    {
+                        "type": "image_url",
+                        "image_url": { "url": "data:image/png;base64,iVBORw0KGgo=", "detail": "low" }
+                    }
+                ],
+                "tool_call_id": "call_1"
+            })
+        );
+    }
+
+    #[test]
+    fn serialize_user_content_parts() {
+        let message = Message::user(vec![
+            ContentPart::Text {
+                text: "What is in this image?".to_string(),
+            },
+            ContentPart::ImageUrl {
+                image_url: ImageUrl {
+                    url: "https://example.com/cat.png".to_string(),
+                    detail: None,
+                },
+            },
+        ]);
+        assert_eq!(
+            serde_json::to_value(&message).unwrap(),
+            serde_json::json!({
+                "role": "user",
+                "content": [
+                    { "type": "text", "text": "What is in this image?" },
+                    { "type": "image_url", "image_url": { "url": "https://example.com/cat.png" } }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn image_from_path() {
+        // A 1x1 transparent PNG.
+        const PNG: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+        let path =
+            std::env::temp_dir().join(format!("openai-interface-{}.PNG", std::process::id()));
+        std::fs::write(
+            &path,
+            base64::engine::general_purpose::STANDARD
+                .decode(PNG)
+                .unwrap(),
+        )
+        .unwrap();
+
+        let part = ContentPart::image_from_path(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            part,
+            ContentPart::ImageUrl {
+                image_url: ImageUrl {
+                    url: format!("data:image/png;base64,{}", PNG),
+                    detail: None,
+                },
+            }
+        );
+
+        assert!(matches!(
+            ContentPart::image_from_path("notes.txt"),
+            Err(OapiError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            ContentPart::image_from_path("missing.jpg"),
+            Err(OapiError::FileNotFoundError(_))
+        ));
+    }
+
+    #[test]
+    fn serialize_stream_options() {
+        let options = StreamOptions {
+            include_usage: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&options).unwrap(),
+            serde_json::json!({ "include_usage": true })
+        );
+
+        let options = StreamOptions {
+            include_obfuscation: Some(false),
+            include_usage: false,
+        };
+        assert_eq!(
+            serde_json::to_value(&options).unwrap(),
+            serde_json::json!({ "include_obfuscation": false, "include_usage": false })
+        );
+    }
+
+    #[test]
+    fn validate_max_completion_tokens() {
+        let mut request = RequestBody {
+            model: DEEPSEEK_MODEL.to_string(),
+            max_completion_tokens: Some(8192),
+            ..Default::default()
+        };
+        assert!(request.validate().is_ok());
+
+        request.max_completion_tokens = Some(8193);
+        assert!(matches!(
+            request.validate(),
+            Err(OapiError::InvalidParameter(_))
+        ));
+
+        request.max_completion_tokens = None;
+        request.max_tokens = Some(100_000);
+        assert!(matches!(
+            request.validate(),
+            Err(OapiError::InvalidParameter(_))
+        ));
+
+        request.max_tokens = Some(100);
+        request.max_completion_tokens = Some(100);
+        match request.validate() {
+            Err(OapiError::InvalidParameter(message)) => {
+                assert!(message.contains("both set"), "{}", message)
+            }
+            other => panic!("expected an invalid parameter, got {:?}", other),
+        }
+
+        let request = request.with_max_completion_tokens(200);
+        assert!(request.validate().is_ok());
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["max_completion_tokens"], 200);
+        assert!(body.get("max_tokens").is_none());
+    }
+
+    #[test]
+    fn extra_fields_precedence() {
+        let request = RequestBody {
+            model: DEEPSEEK_MODEL.to_string(),
+            temperature: Some(0.5),
+            extra_body: Some(ExtraBody {
+                enable_thinking: Some(true),
+                thinking_budget: None,
+                top_k: None,
+            }),
+            ..Default::default()
+        }
+        .extra("enable_thinking", false)
+        .extra("thinking_budget", 1024)
+        .extra("model", "other-model")
+        .extra("user", "user-1234")
+        .extra("top_p", 0.9);
+
+        let body = serde_json::to_string(&request).unwrap();
+        assert_eq!(body.matches("\"enable_thinking\"").count(), 1);
+        assert_eq!(body.matches("\"model\"").count(), 1);
+
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["enable_thinking"], true);
+        assert_eq!(body["thinking_budget"], 1024);
+        assert_eq!(body["model"], DEEPSEEK_MODEL);
+        assert_eq!(body["user"], "user-1234");
+        assert_eq!(body["temperature"], 0.5);
+        assert_eq!(body["top_p"], 0.9);
+
+        let request = RequestBody {
+            model: DEEPSEEK_MODEL.to_string(),
+            ..Default::default()
+        }
+        .extra("temperature", 0.3)
+        .extra("top_p", 0.9);
+
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["temperature"], 0.3);
+        assert_eq!(body["top_p"], 0.9);
+    }
+
+    #[test]
+    fn json_schema_from_value() {
+        let schema = JSONSchema::from_value(
+            "weather_report",
+            "The weather of a city.",
+            serde_json::json!({
+                "type": "object",
+                "properties": {"city": {"type": "string"}},
+                "required": ["city"],
+            }),
+            Some(true),
+        )
+        .unwrap();
+        assert_eq!(schema.schema["type"], "object");
+        let format = serde_json::to_value(ResponseFormat::JsonSchema {
+            json_schema: schema,
+        })
+        .unwrap();
+        assert_eq!(format["json_schema"]["name"], "weather_report");
+        assert_eq!(format["json_schema"]["strict"], true);
+
+        for (name, schema) in [
+            ("weather_report", serde_json::json!(["city"])),
+            ("weather report", serde_json::json!({"type": "object"})),
+            ("", serde_json::json!({"type": "object"})),
+        ] {
+            assert!(matches!(
+                JSONSchema::from_value(name, "", schema, None),
+                Err(OapiError::InvalidParameter(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn prediction_body() {
+        let request = RequestBody {
+            model: "gpt-4o".to_string(),
+            prediction: Some(Prediction::content("fn main() {}")),
+            ..Default::default()
+        };
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            body["prediction"],
+            serde_json::json!({"type": "content", "content": "fn main() {}"})
+        );
+
+        let request = RequestBody {
+            model: "gpt-4o".to_string(),
+            ..Default::default()
+        };
+        let body = serde_json::to_value(&request).unwrap();
+        assert!(body.get("prediction").is_none());
+    }
+
+    #[test]
+    fn clone_template() {
+        let base = RequestBody {
+            messages: vec![Message::system("Be brief.")],
+            model: DEEPSEEK_MODEL.to_string(),
+            temperature: Some(0.2),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&base).unwrap(),
+            serde_json::to_value(base.clone()).unwrap()
+        );
+
+        let mut request = RequestBody {
+            max_tokens: Some(100),
+            ..base.clone()
+        };
+        request.messages.push(Message::user("Hello!"));
+        assert_eq!(base.messages.len(), 1);
+        assert_eq!(base.max_tokens, None);
+        assert_eq!(request.temperature, Some(0.2));
+    }
+
+    #[test]
+    fn validate_ranges() {
+        let mut request = RequestBody {
+            model: "my-local-model".to_string(),
+            temperature: Some(2.0),
+            top_p: Some(0.0),
+            frequency_penalty: Some(-2.0),
+            presence_penalty: Some(2.0),
+            top_logprobs: Some(20),
+            ..Default::default()
+        };
+        assert!(request.validate().is_ok());
+
+        request.temperature = Some(20.0);
+        match request.validate() {
+            Err(OapiError::InvalidParameter(message)) => {
+                assert_eq!(message, "`temperature` is 20, but must be between 0 and 2")
+            }
+            other => panic!("expected an invalid parameter, got {:?}", other),
+        }
+
+        request.temperature = Some(f32::NAN);
+        assert!(request.validate().is_err());
+
+        request.temperature = None;
+        request.logit_bias = Some(HashMap::from([(50256, -100), (1734, 101), (9, 127)]));
+        match request.validate() {
+            Err(OapiError::InvalidParameter(message)) => assert_eq!(
+                message,
+                "`logit_bias` of token 9 is 127, but must be between -100 and 100"
+            ),
+            other => panic!("expected an invalid parameter, got {:?}", other),
+        }
+        assert_eq!(
+            serde_json::to_value(&request).unwrap()["logit_bias"],
+            serde_json::json!({ "50256": -100, "1734": 101, "9": 127 })
+        );
+        request.logit_bias = None;
+
+        request.temperature = None;
+        request.top_logprobs = Some(21);
+        request.model = DEEPSEEK_MODEL.to_string();
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn validate_tool_call_ids() {
+        let tool_call = |id: &str| AssistantToolCall::Function {
+            id: id.to_string(),
+            function: ToolCallFunction {
+                arguments: "{}".to_string(),
+                name: "get_weather".to_string(),
+            },
+        };
+        let tool_result = |id: &str| Message::Tool {
+            content: "25°C".into(),
+            tool_call_id: id.to_string(),
+        };
+        let mut request = RequestBody {
+            messages: vec![
+                Message::Assistant {
+                    content: None,
+                    refusal: None,
+                    name: None,
+                    prefix: false,
+                    reasoning_content: None,
+                    tool_calls: Some(vec![tool_call("call_0")]),
+                },
+                tool_result("call_0"),
+            ],
+            model: "my-local-model".to_string(),
+            ..Default::default()
+        };
+        assert!(request.validate().is_ok());
+
+        request.messages.push(tool_result("call_9"));
+        request.messages.push(tool_result("call_8"));
+        match request.validate() {
+            Err(OapiError::InvalidParameter(message)) => {
+                assert!(message.ends_with("`call_9`, `call_8`"), "{}", message)
+            }
+            other => panic!("expected an invalid parameter, got {:?}", other),
+        }
+
+        request.model = DEEPSEEK_MODEL.to_string();
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn validate_unknown_model() {
+        let mut request = RequestBody {
+            model: "my-local-model".to_string(),
+            max_completion_tokens: Some(100_000),
+            ..Default::default()
+        };
+        assert!(request.validate().is_ok());
+
+        let limits = ModelLimits {
+            context_window: 32_768,
+            max_output_tokens: 4_096,
+        };
+        assert!(request.validate_with_limits(limits).is_err());
+        request.max_completion_tokens = Some(4_096);
+        assert!(request.validate_with_limits(limits).is_ok());
+    }
 
     #[tokio::test]
     async fn test_deepseek_no_stream() {
@@ -551,7 +1714,7 @@ mod request_test {
                     name: None,
                 },
                 Message::User {
-                    content: "What's your name?".to_string(),
+                    content: "What's your name?".into(),
                     name: None,
                 },
             ],
@@ -561,7 +1724,7 @@ mod request_test {
         };
 
         let response = request
-            .get_response_string(DEEPSEEK_CHAT_URL, &*DEEPSEEK_API_KEY)
+            .get_response_string(DEEPSEEK_CHAT_URL, *DEEPSEEK_API_KEY)
             .await
             .unwrap();
 
@@ -579,7 +1742,7 @@ mod request_test {
                     name: None,
                 },
                 Message::User {
-                    content: "What's your name?".to_string(),
+                    content: "What's your name?".into(),
                     name: None,
                 },
             ],
@@ -1,11 +1,21 @@
 pub mod streaming {
-    use std::str::FromStr;
+    use std::{collections::BTreeMap, str::FromStr};
 
-    use serde::Deserialize;
+    use futures_util::{Stream, StreamExt};
+    use serde::{Deserialize, Serialize};
 
     use crate::errors::OapiError;
 
-    #[derive(Debug, Deserialize, Clone)]
+    use super::no_streaming::{
+        ChatCompletion, ChatCompletionMessage, ChatCompletionMessageToolCall, ChatCompletionObject,
+        Choice, MessageToolCallFunction, ResponseRole,
+    };
+    pub use super::no_streaming::{
+        ChoiceLogprobs, CompletionTokensDetails, CompletionUsage, FinishReason,
+        PromptTokensDetails, ServiceTier, TokenLogProb, TopLogprob,
+    };
+
+    #[derive(Debug, Deserialize, Serialize, Clone)]
     pub struct ChatCompletionChunk {
         /// A unique identifier for the chat completion.
         pub id: String,
@@ -18,15 +28,18 @@ pub mod streaming {
         pub created: u64,
         /// The model used for the chat completion.
         pub model: String,
-        /// The object type, which is always `chat.completion.chunk`
+        /// The object type, which is always `chat.completion.chunk`. Gateways
+        /// may omit it or send another value.
+        #[serde(default)]
         pub object: ChatCompletionChunkObject,
-        /// This fingerprint represents the backend configuration that the model runs with.
-        /// Can be used in conjunction with the `seed` request parameter to understand when
-        /// backend changes have been made that might impact determinism.
+        /// The service tier actually used to serve the request, echoed when the
+        /// `service_tier` request parameter is set. It may differ from the
+        /// requested tier. [`StreamAccumulator`] keeps the last one sent.
         pub service_tier: Option<ServiceTier>,
         /// This fingerprint represents the backend configuration that the model runs with.
         /// Can be used in conjunction with the `seed` request parameter to understand when
         /// backend changes have been made that might impact determinism.
+        #[serde(default, deserialize_with = "crate::de::string_or_number")]
         pub system_fingerprint: Option<String>,
         /// An optional field that will only be present when you set
         /// `stream_options: {"include_usage": true}` in your request. When present, it
@@ -38,33 +51,27 @@ pub mod streaming {
         pub usage: Option<CompletionUsage>,
     }
 
-    #[derive(Debug, Deserialize, Clone)]
+    #[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
     pub enum ChatCompletionChunkObject {
+        #[default]
         #[serde(rename = "chat.completion.chunk")]
         ChatCompletionChunk,
+        /// Another object type, sent by some OpenAI-compatible gateways.
+        #[serde(untagged)]
+        Other(String),
     }
 
-    /// The service tier used for processing the request.
-    ///
-    /// This enum represents the different service tiers that can be specified when
-    /// making a request to the API. Each tier corresponds to different performance
-    /// characteristics and pricing models.
-    #[derive(Debug, Deserialize, Clone)]
-    #[serde(rename_all = "lowercase")]
-    pub enum ServiceTier {
-        /// Automatically select the service tier based on project settings.
-        Auto,
-        /// Use the default service tier with standard pricing and performance.
-        Default,
-        /// Use the flex service tier for flexible processing requirements.
-        Flex,
-        /// Use the scale service tier for scalable processing needs.
-        Scale,
-        /// Use the priority service tier for high-priority requests.
-        Priority,
+    impl ChatCompletionChunkObject {
+        /// Returns the object type as sent by the provider.
+        pub fn as_str(&self) -> &str {
+            match self {
+                Self::ChatCompletionChunk => "chat.completion.chunk",
+                Self::Other(object) => object,
+            }
+        }
     }
 
-    #[derive(Debug, Deserialize, Clone)]
+    #[derive(Debug, Deserialize, Serialize, Clone)]
     pub struct CompletionChunkChoice {
         /// A chat completion delta generated by streamed model responses.
         pub delta: ChoiceDelta,
@@ -80,30 +87,28 @@ pub mod streaming {
         /// filters, `tool_calls` if the model called a tool, or `function_call`
         /// (deprecated) if the model called a function.
         pub finish_reason: Option<FinishReason>,
+        /// The results of the content filters of the provider, as sent by Azure
+        /// OpenAI. Its shape varies by provider, so it is kept as raw JSON.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub content_filter_results: Option<serde_json::Value>,
     }
 
-    #[derive(Debug, Deserialize, Clone)]
-    #[serde(rename_all = "snake_case")]
-    pub enum FinishReason {
-        /// The maximum number of tokens specified in the request was reached.
-        Length,
-        /// The model hit a natural stop point or a provided stop sequence.
-        Stop,
-        /// Content was omitted due to a flag from our content filters.
-        ContentFilter,
-        /// The model called a function (deprecated).
-        FunctionCall,
-        /// The model called a tool.
-        ToolCalls,
-        /// This choice can only be found in the manual of DeepSeek.
-        InsufficientSystemResource,
-    }
-
-    #[derive(Debug, Deserialize, Clone)]
+    #[derive(Debug, Deserialize, Serialize, Clone)]
+    #[serde(from = "RawChoiceDelta")]
     pub struct ChoiceDelta {
         /// The contents of the chunk message.
+        ///
+        /// Reasoning models send both `content` and `reasoning_content`, with
+        /// the one not being streamed set to `null` or `""`. The other one is
+        /// kept here. When both carry text, the answer is kept here and the
+        /// reasoning in `reasoning_content`.
         #[serde(flatten)]
         pub content: Option<CompletionContent>,
+        /// The reasoning of a chunk whose `content` is an answer as well, as
+        /// sent by providers that switch from reasoning to answering within one
+        /// chunk. The reasoning comes before the answer.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub reasoning_content: Option<String>,
         /// Deprecated and replaced by `tool_calls`.
         ///
         /// The name and arguments of a function that should be called, as generated by the
@@ -117,7 +122,50 @@ pub mod streaming {
         pub tool_calls: Option<Vec<ChoiceDeltaToolCall>>,
     }
 
-    #[derive(Debug, Deserialize, Clone)]
+    /// [`ChoiceDelta`] as sent by the provider, with `content` and
+    /// `reasoning_content` as separate fields.
+    #[derive(Deserialize)]
+    struct RawChoiceDelta {
+        #[serde(default)]
+        content: Option<String>,
+        #[serde(default)]
+        reasoning_content: Option<String>,
+        #[serde(default)]
+        function_call: Option<ChoiceDeltaFunctionCall>,
+        #[serde(default)]
+        refusal: Option<String>,
+        #[serde(default)]
+        role: Option<CompletionRole>,
+        #[serde(default)]
+        tool_calls: Option<Vec<ChoiceDeltaToolCall>>,
+    }
+
+    impl From<RawChoiceDelta> for ChoiceDelta {
+        fn from(raw: RawChoiceDelta) -> Self {
+            let (content, reasoning_content) = match (raw.content, raw.reasoning_content) {
+                (content, Some(reasoning))
+                    if !reasoning.is_empty() && content.as_ref().is_none_or(String::is_empty) =>
+                {
+                    (Some(CompletionContent::ReasoningContent(reasoning)), None)
+                }
+                (Some(content), reasoning) => (
+                    Some(CompletionContent::Content(content)),
+                    reasoning.filter(|reasoning| !reasoning.is_empty()),
+                ),
+                (None, reasoning) => (reasoning.map(CompletionContent::ReasoningContent), None),
+            };
+            Self {
+                content,
+                reasoning_content,
+                function_call: raw.function_call,
+                refusal: raw.refusal,
+                role: raw.role,
+                tool_calls: raw.tool_calls,
+            }
+        }
+    }
+
+    #[derive(Debug, Deserialize, Serialize, Clone)]
     pub struct ChoiceDeltaToolCallFunction {
         /// The arguments to call the function with, as generated by the model in JSON
         /// format. Note that the model does not always generate valid JSON, and may
@@ -128,7 +176,7 @@ pub mod streaming {
         pub name: Option<String>,
     }
 
-    #[derive(Debug, Deserialize, Clone)]
+    #[derive(Debug, Deserialize, Serialize, Clone)]
     pub struct ChoiceDeltaFunctionCall {
         /// The arguments to call the function with, as generated by the model in JSON
         /// format. Note that the model does not always generate valid JSON, and may
@@ -139,7 +187,7 @@ pub mod streaming {
         pub name: Option<String>,
     }
 
-    #[derive(Debug, Deserialize, Clone)]
+    #[derive(Debug, Deserialize, Serialize, Clone)]
     pub struct ChoiceDeltaToolCall {
         /// The index of the tool call in the list of tool calls.
         pub index: usize,
@@ -152,13 +200,16 @@ pub mod streaming {
         pub type_: Option<ChoiceDeltaToolCallType>,
     }
 
-    #[derive(Debug, Deserialize, Clone)]
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
     #[serde(rename_all = "snake_case")]
     pub enum ChoiceDeltaToolCallType {
         Function,
+        /// A tool type this crate does not know yet.
+        #[serde(untagged)]
+        Other(String),
     }
 
-    #[derive(Debug, Deserialize, Clone)]
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
     #[serde(rename_all = "snake_case")]
     pub enum CompletionRole {
         Assistant,
@@ -166,9 +217,26 @@ pub mod streaming {
         System,
         Tool,
         User,
+        /// A role this crate does not know yet.
+        #[serde(untagged)]
+        Other(String),
     }
 
-    #[derive(Debug, Deserialize, Clone)]
+    impl CompletionRole {
+        /// Returns the role as sent by the provider.
+        pub fn as_str(&self) -> &str {
+            match self {
+                Self::Assistant => "assistant",
+                Self::Developer => "developer",
+                Self::System => "system",
+                Self::Tool => "tool",
+                Self::User => "user",
+                Self::Other(role) => role,
+            }
+        }
+    }
+
+    #[derive(Debug, Deserialize, Serialize, Clone)]
     #[serde(rename_all = "snake_case")]
     pub enum CompletionContent {
         Content(String),
@@ -176,91 +244,406 @@ pub mod streaming {
         ReasoningContent(String),
     }
 
-    #[derive(Debug, Deserialize, Clone)]
-    #[serde(rename_all = "snake_case")]
-    pub enum ChoiceLogprobs {
-        Content(Vec<LogprobeContent>),
-        /// For deepseek-reasoner model only.
-        ReasoningContent(Vec<LogprobeContent>),
+    /// A nameable stream of [`ChatCompletionChunk`]s, returned by
+    /// [`Stream::get_stream_response_unboxed`](crate::rest::post::Stream::get_stream_response_unboxed).
+    pub type ChatCompletionStream = crate::rest::post::ResponseStream<ChatCompletionChunk>;
+
+    /// An event observed while accumulating a stream, returned by
+    /// [`StreamAccumulator::push`].
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum StreamEvent {
+        /// A piece of the message content.
+        Content(String),
+        /// A piece of the reasoning content.
+        ReasoningContent(String),
+        /// A piece of a refusal message. Emitted as soon as the model starts
+        /// refusing, so that UIs can react before the stream ends.
+        Refusal(String),
+        /// The choice at `index` was stopped with `finish_reason:
+        /// content_filter`, instead of ending normally. Carries the last
+        /// `content_filter_results` of the choice, if the provider sent any.
+        Filtered {
+            index: u32,
+            content_filter_results: Option<serde_json::Value>,
+        },
     }
 
-    /// A list of message content tokens with log probability information.
-    #[derive(Debug, Deserialize, Clone)]
-    pub struct LogprobeContent {
-        pub token: String,
-        pub logprob: f32,
-        pub bytes: Option<Vec<u8>>,
-        pub top_logprobs: Vec<TopLogprob>,
+    /// Reassembles the chunks of a streaming chat completion.
+    ///
+    /// Push every chunk in order, then call [`StreamAccumulator::finish`] to get
+    /// the same shape as a non-streaming response.
+    ///
+    /// A connection closed by the provider or the network looks like the normal
+    /// end of the stream. Check [`StreamAccumulator::is_complete`] to tell them
+    /// apart, e.g. to decide whether to retry.
+    #[derive(Debug, Default, Clone)]
+    pub struct StreamAccumulator {
+        /// Set when `[DONE]` or a terminal `finish_reason` was observed.
+        finished_cleanly: bool,
+        id: Option<String>,
+        created: u64,
+        model: String,
+        service_tier: Option<ServiceTier>,
+        system_fingerprint: Option<String>,
+        usage: Option<CompletionUsage>,
+        choices: BTreeMap<u32, AccumulatedChoice>,
     }
 
-    /// List of the most likely tokens and their log probability, at this
-    /// token position. In rare cases, there may be fewer than the number of requested top_logprobs returned.
-    #[derive(Debug, Deserialize, Clone)]
-    pub struct TopLogprob {
-        pub token: String,
-        pub logprob: f32,
-        pub bytes: Option<Vec<u8>>,
+    #[derive(Debug, Default, Clone)]
+    struct AccumulatedChoice {
+        content: Option<String>,
+        reasoning_content: Option<String>,
+        refusal: Option<String>,
+        finish_reason: Option<FinishReason>,
+        content_filter_results: Option<serde_json::Value>,
+        /// Tool calls by their index in the list of tool calls.
+        tool_calls: BTreeMap<usize, AccumulatedToolCall>,
     }
 
-    #[derive(Debug, Deserialize, Clone)]
-    pub struct CompletionUsage {
-        /// Number of tokens in the generated completion.
-        pub completion_tokens: usize,
-        /// Number of tokens in the prompt.
-        pub prompt_tokens: usize,
+    /// A function tool call merged from its deltas. The ID and the name come
+    /// in the first delta of the call; the arguments are streamed in pieces
+    /// over the following ones.
+    #[derive(Debug, Default, Clone)]
+    struct AccumulatedToolCall {
+        id: Option<String>,
+        name: String,
+        arguments: String,
+    }
 
-        // These two fields seem to be DeepSeek specific.
-        /// Number of tokens in the prompt that hits the context cache.
-        pub prompt_cache_hit_tokens: Option<usize>,
-        /// Number of tokens in the prompt that misses the context cache.
-        pub prompt_cache_miss_tokens: Option<usize>,
+    impl StreamAccumulator {
+        pub fn new() -> Self {
+            Self::default()
+        }
 
-        /// Total number of tokens used in the request (prompt + completion).
-        pub total_tokens: usize,
-        /// Breakdown of tokens used in a completion.
-        pub completion_tokens_details: Option<CompletionTokensDetails>,
-        /// Breakdown of tokens used in the prompt.
-        pub prompt_tokens_details: Option<PromptTokensDetails>,
-    }
+        /// Folds a chunk into the accumulated completion, returning the events
+        /// it carried.
+        pub fn push(&mut self, chunk: &ChatCompletionChunk) -> Vec<StreamEvent> {
+            if self.id.is_none() {
+                self.id = Some(chunk.id.clone());
+                self.created = chunk.created;
+                self.model = chunk.model.clone();
+            }
+            if chunk.service_tier.is_some() {
+                self.service_tier = chunk.service_tier.clone();
+            }
+            if chunk.system_fingerprint.is_some() {
+                self.system_fingerprint = chunk.system_fingerprint.clone();
+            }
+            if chunk.usage.is_some() {
+                self.usage = chunk.usage.clone();
+            }
 
-    #[derive(Debug, Deserialize, Clone)]
-    pub struct CompletionTokensDetails {
-        /// When using Predicted Outputs, the number of tokens in the prediction that
-        /// appeared in the completion.
-        pub accepted_prediction_tokens: Option<usize>,
-        /// Audio input tokens generated by the model.
-        pub audio_tokens: Option<usize>,
-        /// Tokens generated by the model for reasoning.
-        pub reasoning_tokens: Option<usize>,
-        /// When using Predicted Outputs, the number of tokens in the prediction that did
-        /// not appear in the completion. However, like reasoning tokens, these tokens are
-        /// still counted in the total completion tokens for purposes of billing, output,
-        /// and context window limits.
-        pub rejected_prediction_tokens: Option<usize>,
+            let mut events = Vec::new();
+            for choice in &chunk.choices {
+                let accumulated = self.choices.entry(choice.index).or_default();
+                if let Some(reasoning) = &choice.delta.reasoning_content {
+                    append(&mut accumulated.reasoning_content, reasoning);
+                    events.push(StreamEvent::ReasoningContent(reasoning.clone()));
+                }
+                match &choice.delta.content {
+                    Some(CompletionContent::Content(text)) if !text.is_empty() => {
+                        append(&mut accumulated.content, text);
+                        events.push(StreamEvent::Content(text.clone()));
+                    }
+                    Some(CompletionContent::ReasoningContent(text)) if !text.is_empty() => {
+                        append(&mut accumulated.reasoning_content, text);
+                        events.push(StreamEvent::ReasoningContent(text.clone()));
+                    }
+                    _ => {}
+                }
+                if let Some(refusal) = choice.delta.refusal.as_ref().filter(|r| !r.is_empty()) {
+                    append(&mut accumulated.refusal, refusal);
+                    events.push(StreamEvent::Refusal(refusal.clone()));
+                }
+                for tool_call in choice.delta.tool_calls.iter().flatten() {
+                    let accumulated = accumulated.tool_calls.entry(tool_call.index).or_default();
+                    if tool_call.id.is_some() {
+                        accumulated.id = tool_call.id.clone();
+                    }
+                    if let Some(function) = &tool_call.function {
+                        if let Some(name) = &function.name {
+                            accumulated.name.push_str(name);
+                        }
+                        if let Some(arguments) = &function.arguments {
+                            accumulated.arguments.push_str(arguments);
+                        }
+                    }
+                }
+                if choice.content_filter_results.is_some() {
+                    accumulated.content_filter_results = choice.content_filter_results.clone();
+                }
+                if choice.finish_reason.is_some() {
+                    accumulated.finish_reason = choice.finish_reason.clone();
+                }
+                if choice.finish_reason == Some(FinishReason::ContentFilter) {
+                    events.push(StreamEvent::Filtered {
+                        index: choice.index,
+                        content_filter_results: accumulated.content_filter_results.clone(),
+                    });
+                }
+            }
+            if !self.choices.is_empty()
+                && self
+                    .choices
+                    .values()
+                    .all(|choice| choice.finish_reason.is_some())
+            {
+                self.finished_cleanly = true;
+            }
+            events
+        }
+
+        /// Records that the stream ended with the `[DONE]` sentinel, as told by
+        /// [`ResponseStream::received_done`](crate::rest::post::ResponseStream::received_done).
+        pub fn push_done(&mut self) {
+            self.finished_cleanly = true;
+        }
+
+        /// Whether the stream ended properly: either `[DONE]` was received, or
+        /// every choice received its terminal `finish_reason`.
+        pub fn is_complete(&self) -> bool {
+            self.finished_cleanly
+        }
+
+        /// Builds the full completion from the chunks pushed so far.
+        ///
+        /// Fails if no chunk was pushed, or if a choice never received its
+        /// `finish_reason`, which means the stream was cut off.
+        pub fn finish(self) -> Result<ChatCompletion, OapiError> {
+            let id = self
+                .id
+                .ok_or_else(|| OapiError::StreamError("No chunk was received".to_string()))?;
+
+            let choices = self
+                .choices
+                .into_iter()
+                .map(|(index, choice)| {
+                    let finish_reason = choice.finish_reason.ok_or_else(|| {
+                        OapiError::StreamError(format!(
+                            "The stream ended before choice {} finished",
+                            index
+                        ))
+                    })?;
+                    let tool_calls = (!choice.tool_calls.is_empty()).then(|| {
+                        choice
+                            .tool_calls
+                            .into_values()
+                            .map(|tool_call| ChatCompletionMessageToolCall::Function {
+                                id: tool_call.id.unwrap_or_default(),
+                                function: MessageToolCallFunction {
+                                    arguments: tool_call.arguments,
+                                    name: tool_call.name,
+                                },
+                            })
+                            .collect()
+                    });
+                    Ok(Choice {
+                        finish_reason,
+                        index: index as usize,
+                        logprobs: None,
+                        message: ChatCompletionMessage {
+                            role: ResponseRole::Assistant,
+                            content: choice.content,
+                            reasoning_content: choice.reasoning_content,
+                            refusal: choice.refusal,
+                            tool_calls,
+                            annotations: None,
+                        },
+                    })
+                })
+                .collect::<Result<Vec<_>, OapiError>>()?;
+
+            Ok(ChatCompletion {
+                id,
+                choices,
+                created: self.created,
+                model: self.model,
+                service_tier: self.service_tier,
+                system_fingerprint: self.system_fingerprint,
+                object: ChatCompletionObject::ChatCompletion,
+                usage: self.usage,
+            })
+        }
     }
 
-    #[derive(Debug, Deserialize, Clone)]
-    pub struct PromptTokensDetails {
-        /// Audio input tokens present in the prompt.
-        pub audio_tokens: Option<usize>,
-        /// Cached tokens present in the prompt.
-        pub cached_tokens: Option<usize>,
+    #[inline]
+    fn append(buffer: &mut Option<String>, text: &str) {
+        buffer.get_or_insert_with(String::new).push_str(text);
     }
 
     impl FromStr for ChatCompletionChunk {
         type Err = crate::errors::OapiError;
 
         fn from_str(content: &str) -> Result<Self, Self::Err> {
-            let parse_result: Result<ChatCompletionChunk, _> = serde_json::from_str(content)
-                .map_err(|e| OapiError::DeserializationError(e.to_string()));
-            parse_result
+            Ok(serde_json::from_str(content)?)
+        }
+    }
+
+    impl ChatCompletionChunk {
+        /// Serializes the chunk as a server-sent event, `data: {...}\n\n`, the
+        /// way providers send it.
+        pub fn to_sse_frame(&self) -> String {
+            let data = serde_json::to_string(self).expect("chunks always serialize to JSON");
+            format!("data: {}\n\n", data)
         }
     }
 
+    /// The event ending a chat completion stream.
+    pub const SSE_DONE_FRAME: &str = "data: [DONE]\n\n";
+
+    /// Turns chunks into server-sent event frames, followed by the closing
+    /// `data: [DONE]` frame, e.g. to forward an upstream stream to your own
+    /// clients after modifying it.
+    pub fn to_sse_frames<S>(chunks: S) -> impl Stream<Item = String>
+    where
+        S: Stream<Item = ChatCompletionChunk>,
+    {
+        chunks
+            .map(|chunk| chunk.to_sse_frame())
+            .chain(futures_util::stream::once(std::future::ready(
+                SSE_DONE_FRAME.to_string(),
+            )))
+    }
+
+    /// Keeps only the answer text of the first choice of a stream of chunks.
+    ///
+    /// Chunks without text, such as the role-only first chunk, the usage chunk
+    /// or the ones with `reasoning_content`, are skipped. Errors are passed
+    /// through.
+    pub fn text_stream<S>(chunks: S) -> impl Stream<Item = Result<String, OapiError>>
+    where
+        S: Stream<Item = Result<ChatCompletionChunk, OapiError>>,
+    {
+        chunks.filter_map(|chunk| {
+            std::future::ready(match chunk {
+                Ok(chunk) => chunk
+                    .choices
+                    .into_iter()
+                    .find(|choice| choice.index == 0)
+                    .and_then(|choice| match choice.delta.content {
+                        Some(CompletionContent::Content(text)) if !text.is_empty() => {
+                            Some(Ok(text))
+                        }
+                        _ => None,
+                    }),
+                Err(e) => Some(Err(e)),
+            })
+        })
+    }
+
+    /// Splits the first choice of a reasoning model's stream into its chain of
+    /// thought and its answer, as `(reasoning, answer)`.
+    ///
+    /// Pieces of `reasoning_content` and `content` are concatenated separately,
+    /// so the result does not depend on where the model switches from
+    /// reasoning to answering. To split every choice, or to keep the rest of
+    /// the completion, use a [`StreamAccumulator`].
+    pub fn split_reasoning<'a>(
+        chunks: impl IntoIterator<Item = &'a ChatCompletionChunk>,
+    ) -> (String, String) {
+        let mut reasoning = String::new();
+        let mut answer = String::new();
+        for choice in chunks
+            .into_iter()
+            .flat_map(|chunk| &chunk.choices)
+            .filter(|choice| choice.index == 0)
+        {
+            if let Some(text) = &choice.delta.reasoning_content {
+                reasoning.push_str(text);
+            }
+            match &choice.delta.content {
+                Some(CompletionContent::ReasoningContent(text)) => reasoning.push_str(text),
+                Some(CompletionContent::Content(text)) => answer.push_str(text),
+                None => {}
+            }
+        }
+        (reasoning, answer)
+    }
+
     #[cfg(test)]
     mod test {
         use super::*;
 
+        #[tokio::test]
+        async fn sse_frame_round_trip() {
+            let data = r#"{"id": "1", "choices": [{"index": 0, "delta": {"content": "Hello", "role": "assistant", "tool_calls": [{"index": 0, "id": "call_0", "type": "function", "function": {"name": "get_weather", "arguments": "{}"}}]}, "finish_reason": null, "logprobs": null}], "created": 1718345013, "model": "deepseek-chat", "object": "chat.completion.chunk", "service_tier": "default", "usage": {"prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6}}"#;
+            let chunk = super::super::streaming::ChatCompletionChunk::from_str(data).unwrap();
+
+            let frame = chunk.to_sse_frame();
+            assert!(frame.starts_with("data: {"));
+            assert!(frame.ends_with("}\n\n"));
+
+            let reparsed =
+                ChatCompletionChunk::from_str(frame.strip_prefix("data: ").unwrap().trim_end())
+                    .unwrap();
+            assert_eq!(reparsed.to_sse_frame(), frame);
+            let original: serde_json::Value = serde_json::from_str(data).unwrap();
+            let value = serde_json::to_value(&reparsed).unwrap();
+            for field in ["content", "role", "tool_calls"] {
+                assert_eq!(
+                    value["choices"][0]["delta"][field],
+                    original["choices"][0]["delta"][field]
+                );
+            }
+            assert_eq!(value["usage"]["total_tokens"], 6);
+
+            let frames: Vec<String> =
+                to_sse_frames(futures_util::stream::iter(vec![chunk.clone(), chunk]))
+                    .collect()
+                    .await;
+            assert_eq!(frames.len(), 3);
+            assert_eq!(frames[0], frame);
+            assert_eq!(frames[2], SSE_DONE_FRAME);
+        }
+
+        #[test]
+        fn chunk_logprobs() {
+            let chunk = |logprobs: &str| {
+                ChatCompletionChunk::from_str(&format!(
+                    r#"{{"id": "1", "choices": [{{"index": 0, "delta": {{"content": "Hi"}}, "finish_reason": null{}}}], "created": 1718345013, "model": "gpt-4o", "object": "chat.completion.chunk"}}"#,
+                    logprobs
+                ))
+                .unwrap()
+            };
+
+            assert_eq!(chunk(r#", "logprobs": null"#).choices[0].logprobs, None);
+            assert_eq!(chunk("").choices[0].logprobs, None);
+
+            let present = chunk(
+                r#", "logprobs": {"content": [{"token": "Hi", "logprob": -0.01, "bytes": [72, 105], "top_logprobs": [{"token": "Hi", "logprob": -0.01, "bytes": [72, 105]}]}], "refusal": null}"#,
+            );
+            let logprobs = present.choices[0].logprobs.as_ref().unwrap();
+            let content = logprobs.content.as_ref().unwrap();
+            assert_eq!(content[0].token, "Hi");
+            assert_eq!(content[0].bytes, Some(vec![72, 105]));
+            assert_eq!(content[0].top_logprobs[0].logprob, -0.01);
+            assert_eq!(logprobs.refusal, None);
+        }
+
+        #[test]
+        fn unknown_chunk_values() {
+            let chunk = super::super::streaming::ChatCompletionChunk::from_str(
+                r#"{"id": "1", "choices": [{"index": 0, "delta": {"role": "critic", "tool_calls": [{"index": 0, "id": "call_0", "type": "web_search"}]}, "finish_reason": "paused", "logprobs": null}], "created": 1718345013, "model": "deepseek-chat", "object": "gateway.chunk", "service_tier": "batch"}"#,
+            )
+            .unwrap();
+            assert_eq!(chunk.object.as_str(), "gateway.chunk");
+
+            let choice = &chunk.choices[0];
+            assert_eq!(choice.delta.role.as_ref().unwrap().as_str(), "critic");
+            assert_eq!(
+                choice.delta.tool_calls.as_ref().unwrap()[0].type_,
+                Some(ChoiceDeltaToolCallType::Other("web_search".to_string()))
+            );
+            assert_eq!(choice.finish_reason.as_ref().unwrap().as_str(), "paused");
+            assert_eq!(chunk.service_tier.unwrap().as_str(), "batch");
+
+            let chunk = super::super::streaming::ChatCompletionChunk::from_str(
+                r#"{"id": "1", "choices": [], "created": 1718345013, "model": "deepseek-chat"}"#,
+            )
+            .unwrap();
+            assert_eq!(chunk.object, ChatCompletionChunkObject::ChatCompletionChunk);
+        }
+
         #[test]
         fn streaming_example_deepseek() {
             let streams = vec![
@@ -317,14 +700,251 @@ pub mod streaming {
                 }
             }
         }
+
+        #[test]
+        fn accumulate_reasoning_and_usage() {
+            let streams = vec![
+                r#"{"id":"1","choices":[{"delta":{"reasoning_content":"9.11 < 9.8","role":"assistant"},"finish_reason":null,"index":0,"logprobs":null}],"created":1735113344,"model":"deepseek-reasoner","object":"chat.completion.chunk","system_fingerprint":"fp_7e0991cad4"}"#,
+                r#"{"id":"1","choices":[{"delta":{"reasoning_content":" since 1 < 8."},"finish_reason":null,"index":0,"logprobs":null}],"created":1735113344,"model":"deepseek-reasoner","object":"chat.completion.chunk","system_fingerprint":"fp_7e0991cad4"}"#,
+                r#"{"id":"1","choices":[{"delta":{"content":"9.11 is"},"finish_reason":null,"index":0,"logprobs":null}],"created":1735113344,"model":"deepseek-reasoner","object":"chat.completion.chunk","system_fingerprint":"fp_7e0991cad4"}"#,
+                r#"{"id":"1","choices":[{"delta":{"content":" smaller."},"finish_reason":"stop","index":0,"logprobs":null}],"created":1735113344,"model":"deepseek-reasoner","object":"chat.completion.chunk","system_fingerprint":"fp_7e0991cad4"}"#,
+                r#"{"id":"1","choices":[],"created":1735113344,"model":"deepseek-reasoner","object":"chat.completion.chunk","system_fingerprint":"fp_7e0991cad4","usage":{"completion_tokens":12,"prompt_tokens":18,"total_tokens":30,"prompt_cache_hit_tokens":16,"prompt_cache_miss_tokens":2,"completion_tokens_details":{"reasoning_tokens":8},"prompt_tokens_details":{"cached_tokens":16}}}"#,
+            ];
+
+            let mut accumulator = StreamAccumulator::new();
+            for stream in streams {
+                let chunk = super::super::streaming::ChatCompletionChunk::from_str(stream).unwrap();
+                accumulator.push(&chunk);
+            }
+
+            let completion = accumulator.finish().unwrap();
+            assert_eq!(completion.id, "1");
+            assert_eq!(completion.model, "deepseek-reasoner");
+            assert_eq!(
+                completion.system_fingerprint.as_deref(),
+                Some("fp_7e0991cad4")
+            );
+            let message = &completion.choices[0].message;
+            assert_eq!(message.role, ResponseRole::Assistant);
+            assert_eq!(message.content.as_deref(), Some("9.11 is smaller."));
+            assert_eq!(
+                message.reasoning_content.as_deref(),
+                Some("9.11 < 9.8 since 1 < 8.")
+            );
+            assert_eq!(completion.choices[0].finish_reason, FinishReason::Stop);
+            let usage = completion.usage.unwrap();
+            assert_eq!(
+                (
+                    usage.prompt_tokens,
+                    usage.completion_tokens,
+                    usage.total_tokens
+                ),
+                (18, 12, 30)
+            );
+            assert_eq!(usage.prompt_cache_hit_tokens, Some(16));
+            assert_eq!(usage.prompt_cache_miss_tokens, Some(2));
+            assert_eq!(
+                usage.completion_tokens_details.unwrap().reasoning_tokens,
+                Some(8)
+            );
+            assert_eq!(usage.prompt_tokens_details.unwrap().cached_tokens, Some(16));
+        }
+
+        #[test]
+        fn split_deepseek_reasoning() {
+            // DeepSeek sends both fields in every delta, the idle one as null.
+            let chunks: Vec<ChatCompletionChunk> = [
+                r#"{"id":"1","choices":[{"index":0,"delta":{"role":"assistant","content":null,"reasoning_content":""},"finish_reason":null}],"created":1,"model":"deepseek-reasoner","object":"chat.completion.chunk"}"#,
+                r#"{"id":"1","choices":[{"index":0,"delta":{"content":null,"reasoning_content":"9.11 < 9.8"},"finish_reason":null}],"created":1,"model":"deepseek-reasoner","object":"chat.completion.chunk"}"#,
+                r#"{"id":"1","choices":[{"index":0,"delta":{"content":null,"reasoning_content":" since 1 < 8."},"finish_reason":null}],"created":1,"model":"deepseek-reasoner","object":"chat.completion.chunk"}"#,
+                r#"{"id":"1","choices":[{"index":0,"delta":{"reasoning_content":null,"content":"9.11 is"},"finish_reason":null}],"created":1,"model":"deepseek-reasoner","object":"chat.completion.chunk"}"#,
+                r#"{"id":"1","choices":[{"index":0,"delta":{"content":" smaller.","reasoning_content":null},"finish_reason":"stop"}],"created":1,"model":"deepseek-reasoner","object":"chat.completion.chunk"}"#,
+            ]
+            .iter()
+            .map(|chunk| ChatCompletionChunk::from_str(chunk).unwrap())
+            .collect();
+
+            assert_eq!(
+                split_reasoning(&chunks),
+                (
+                    "9.11 < 9.8 since 1 < 8.".to_string(),
+                    "9.11 is smaller.".to_string()
+                )
+            );
+
+            let mut accumulator = StreamAccumulator::new();
+            for chunk in &chunks {
+                accumulator.push(chunk);
+            }
+            let message = &accumulator.finish().unwrap().choices[0].message;
+            assert_eq!(
+                message.reasoning_content.as_deref(),
+                Some("9.11 < 9.8 since 1 < 8.")
+            );
+            assert_eq!(message.content.as_deref(), Some("9.11 is smaller."));
+        }
+
+        #[test]
+        fn reasoning_and_content_in_one_chunk() {
+            let chunks: Vec<ChatCompletionChunk> = [
+                r#"{"id":"1","choices":[{"index":0,"delta":{"role":"assistant","content":null,"reasoning_content":"9.11 < 9.8"},"finish_reason":null}],"created":1,"model":"deepseek-reasoner","object":"chat.completion.chunk"}"#,
+                r#"{"id":"1","choices":[{"index":0,"delta":{"content":"9.11 is","reasoning_content":" since 1 < 8."},"finish_reason":null}],"created":1,"model":"deepseek-reasoner","object":"chat.completion.chunk"}"#,
+                r#"{"id":"1","choices":[{"index":0,"delta":{"content":" smaller.","reasoning_content":""},"finish_reason":"stop"}],"created":1,"model":"deepseek-reasoner","object":"chat.completion.chunk"}"#,
+            ]
+            .iter()
+            .map(|chunk| ChatCompletionChunk::from_str(chunk).unwrap())
+            .collect();
+            assert_eq!(
+                chunks[1].choices[0].delta.reasoning_content.as_deref(),
+                Some(" since 1 < 8.")
+            );
+            assert_eq!(chunks[2].choices[0].delta.reasoning_content, None);
+
+            assert_eq!(
+                split_reasoning(&chunks),
+                (
+                    "9.11 < 9.8 since 1 < 8.".to_string(),
+                    "9.11 is smaller.".to_string()
+                )
+            );
+
+            let mut accumulator = StreamAccumulator::new();
+            assert_eq!(
+                accumulator.push(&chunks[0]),
+                vec![StreamEvent::ReasoningContent("9.11 < 9.8".to_string())]
+            );
+            assert_eq!(
+                accumulator.push(&chunks[1]),
+                vec![
+                    StreamEvent::ReasoningContent(" since 1 < 8.".to_string()),
+                    StreamEvent::Content("9.11 is".to_string()),
+                ]
+            );
+            accumulator.push(&chunks[2]);
+            let message = &accumulator.finish().unwrap().choices[0].message;
+            assert_eq!(
+                message.reasoning_content.as_deref(),
+                Some("9.11 < 9.8 since 1 < 8.")
+            );
+            assert_eq!(message.content.as_deref(), Some("9.11 is smaller."));
+        }
+
+        #[test]
+        fn accumulate_refusal() {
+            let streams = vec![
+                r#"{"id":"chatcmpl-123","choices":[{"delta":{"content":"","refusal":null,"role":"assistant"},"finish_reason":null,"index":0,"logprobs":null}],"created":1735113344,"model":"gpt-4o","object":"chat.completion.chunk"}"#,
+                r#"{"id":"chatcmpl-123","choices":[{"delta":{"refusal":"I'm sorry, "},"finish_reason":null,"index":0,"logprobs":null}],"created":1735113344,"model":"gpt-4o","object":"chat.completion.chunk"}"#,
+                r#"{"id":"chatcmpl-123","choices":[{"delta":{"refusal":"I can't help with that."},"finish_reason":null,"index":0,"logprobs":null}],"created":1735113344,"model":"gpt-4o","object":"chat.completion.chunk"}"#,
+                r#"{"id":"chatcmpl-123","choices":[{"delta":{},"finish_reason":"stop","index":0,"logprobs":null}],"created":1735113344,"model":"gpt-4o","object":"chat.completion.chunk"}"#,
+            ];
+
+            let mut accumulator = StreamAccumulator::new();
+            let mut events = Vec::new();
+            for stream in streams {
+                let chunk = super::super::streaming::ChatCompletionChunk::from_str(stream).unwrap();
+                events.extend(accumulator.push(&chunk));
+            }
+
+            assert_eq!(
+                events,
+                vec![
+                    StreamEvent::Refusal("I'm sorry, ".to_string()),
+                    StreamEvent::Refusal("I can't help with that.".to_string()),
+                ]
+            );
+
+            let completion = accumulator.finish().unwrap();
+            let message = &completion.choices[0].message;
+            assert_eq!(
+                message.refusal.as_deref(),
+                Some("I'm sorry, I can't help with that.")
+            );
+            assert!(message.content.is_none());
+            assert_eq!(completion.choices[0].finish_reason, FinishReason::Stop);
+        }
+
+        #[test]
+        fn accumulate_tool_calls() {
+            let streams = vec![
+                r#"{"id":"chatcmpl-123","choices":[{"delta":{"role":"assistant","content":null,"tool_calls":[{"index":0,"id":"call_abc","type":"function","function":{"name":"get_weather","arguments":""}}]},"finish_reason":null,"index":0,"logprobs":null}],"created":1735113344,"model":"gpt-4o","object":"chat.completion.chunk"}"#,
+                r#"{"id":"chatcmpl-123","choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{\"location\":"}}]},"finish_reason":null,"index":0,"logprobs":null}],"created":1735113344,"model":"gpt-4o","object":"chat.completion.chunk"}"#,
+                r#"{"id":"chatcmpl-123","choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":" \"Paris\"}"}}]},"finish_reason":null,"index":0,"logprobs":null}],"created":1735113344,"model":"gpt-4o","object":"chat.completion.chunk"}"#,
+                r#"{"id":"chatcmpl-123","choices":[{"delta":{"tool_calls":[{"index":1,"id":"call_def","type":"function","function":{"name":"get_time","arguments":"{}"}}]},"finish_reason":null,"index":0,"logprobs":null}],"created":1735113344,"model":"gpt-4o","object":"chat.completion.chunk"}"#,
+                r#"{"id":"chatcmpl-123","choices":[{"delta":{},"finish_reason":"tool_calls","index":0,"logprobs":null}],"created":1735113344,"model":"gpt-4o","object":"chat.completion.chunk"}"#,
+            ];
+
+            let mut accumulator = StreamAccumulator::new();
+            for stream in streams {
+                let chunk = super::super::streaming::ChatCompletionChunk::from_str(stream).unwrap();
+                accumulator.push(&chunk);
+            }
+
+            let completion = accumulator.finish().unwrap();
+            assert_eq!(completion.choices[0].finish_reason, FinishReason::ToolCalls);
+            let tool_calls = completion.choices[0].message.tool_calls.as_ref().unwrap();
+            let calls: Vec<(&str, &str, &str)> = tool_calls
+                .iter()
+                .map(|tool_call| match tool_call {
+                    ChatCompletionMessageToolCall::Function { id, function } => (
+                        id.as_str(),
+                        function.name.as_str(),
+                        function.arguments.as_str(),
+                    ),
+                    other => panic!("expected a function call, got {:?}", other),
+                })
+                .collect();
+            assert_eq!(
+                calls,
+                vec![
+                    ("call_abc", "get_weather", r#"{"location": "Paris"}"#),
+                    ("call_def", "get_time", "{}"),
+                ]
+            );
+        }
+
+        #[test]
+        fn accumulate_content_filter() {
+            let streams = vec![
+                r#"{"id":"chatcmpl-123","choices":[{"delta":{"content":"Here is how","role":"assistant"},"finish_reason":null,"index":0,"logprobs":null,"content_filter_results":{"violence":{"filtered":false,"severity":"safe"}}}],"created":1735113344,"model":"gpt-4o","object":"chat.completion.chunk"}"#,
+                r#"{"id":"chatcmpl-123","choices":[{"delta":{},"finish_reason":"content_filter","index":0,"logprobs":null,"content_filter_results":{"violence":{"filtered":true,"severity":"high"}}}],"created":1735113344,"model":"gpt-4o","object":"chat.completion.chunk"}"#,
+            ];
+
+            let mut accumulator = StreamAccumulator::new();
+            let mut events = Vec::new();
+            for stream in streams {
+                let chunk = super::super::streaming::ChatCompletionChunk::from_str(stream).unwrap();
+                events.extend(accumulator.push(&chunk));
+            }
+
+            assert_eq!(
+                events,
+                vec![
+                    StreamEvent::Content("Here is how".to_string()),
+                    StreamEvent::Filtered {
+                        index: 0,
+                        content_filter_results: Some(serde_json::json!({
+                            "violence": { "filtered": true, "severity": "high" }
+                        })),
+                    },
+                ]
+            );
+            assert!(accumulator.is_complete());
+            let completion = accumulator.finish().unwrap();
+            assert!(completion.was_filtered());
+            assert_eq!(
+                completion.choices[0].finish_reason,
+                FinishReason::ContentFilter
+            );
+        }
     }
 }
 
 pub mod no_streaming {
     use std::str::FromStr;
 
-    use serde::Deserialize;
+    use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
+    use crate::chat::request::{AssistantToolCall, Message, ToolCallCustom, ToolCallFunction};
     use crate::errors::OapiError;
 
     #[derive(Debug, Deserialize)]
@@ -358,29 +978,73 @@ pub mod no_streaming {
         /// The system fingerprint used for the chat completion.
         /// Can be used in conjunction with the `seed` request parameter to understand when
         /// backend changes have been made that might impact determinism.
+        #[serde(default, deserialize_with = "crate::de::string_or_number")]
         pub system_fingerprint: Option<String>,
-        /// The object type, which is always `chat.completion`.
+        /// The object type, which is always `chat.completion`. Gateways may
+        /// omit it or send another value.
+        #[serde(default)]
         pub object: ChatCompletionObject,
         /// Usage statistics for the completion request.
         pub usage: Option<CompletionUsage>,
     }
 
-    #[derive(Debug, Deserialize)]
+    /// The service tier used for processing the request.
+    ///
+    /// This enum represents the different service tiers that can be specified when
+    /// making a request to the API. Each tier corresponds to different performance
+    /// characteristics and pricing models.
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
     #[serde(rename_all = "lowercase")]
     pub enum ServiceTier {
+        /// Automatically select the service tier based on project settings.
         Auto,
+        /// Use the default service tier with standard pricing and performance.
         Default,
+        /// Use the flex service tier for flexible processing requirements.
         Flex,
+        /// Use the scale service tier for scalable processing needs.
         Scale,
+        /// Use the priority service tier for high-priority requests.
         Priority,
+        /// A service tier this crate does not know yet.
+        #[serde(untagged)]
+        Other(String),
+    }
+
+    impl ServiceTier {
+        /// Returns the service tier as sent by the provider.
+        pub fn as_str(&self) -> &str {
+            match self {
+                Self::Auto => "auto",
+                Self::Default => "default",
+                Self::Flex => "flex",
+                Self::Scale => "scale",
+                Self::Priority => "priority",
+                Self::Other(tier) => tier,
+            }
+        }
     }
 
     /// The object type, which is always `chat.completion`.
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Default, PartialEq)]
     pub enum ChatCompletionObject {
         /// The object type is always `chat.completion`.
+        #[default]
         #[serde(rename = "chat.completion")]
         ChatCompletion,
+        /// Another object type, sent by some OpenAI-compatible gateways.
+        #[serde(untagged)]
+        Other(String),
+    }
+
+    impl ChatCompletionObject {
+        /// Returns the object type as sent by the provider.
+        pub fn as_str(&self) -> &str {
+            match self {
+                Self::ChatCompletion => "chat.completion",
+                Self::Other(object) => object,
+            }
+        }
     }
 
     #[derive(Debug, Deserialize)]
@@ -401,22 +1065,45 @@ pub mod no_streaming {
         pub message: ChatCompletionMessage,
     }
 
-    #[derive(Debug, Deserialize, PartialEq)]
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
     #[serde(rename_all = "snake_case")]
     pub enum FinishReason {
+        /// The maximum number of tokens specified in the request was reached.
         Length,
+        /// The model hit a natural stop point or a provided stop sequence.
         Stop,
-        ToolCalls,
-        FunctionCall,
+        /// Content was omitted due to a flag from our content filters.
         ContentFilter,
-        /// This choice can only be found in the manual of DeepSeek
+        /// The model called a function (deprecated).
+        FunctionCall,
+        /// The model called a tool.
+        ToolCalls,
+        /// This choice can only be found in the manual of DeepSeek.
         InsufficientSystemResource,
+        /// A finish reason this crate does not know yet.
+        #[serde(untagged)]
+        Other(String),
+    }
+
+    impl FinishReason {
+        /// Returns the finish reason as sent by the provider.
+        pub fn as_str(&self) -> &str {
+            match self {
+                Self::Length => "length",
+                Self::Stop => "stop",
+                Self::ContentFilter => "content_filter",
+                Self::FunctionCall => "function_call",
+                Self::ToolCalls => "tool_calls",
+                Self::InsufficientSystemResource => "insufficient_system_resource",
+                Self::Other(reason) => reason,
+            }
+        }
     }
 
     /// Fields that are not supported yet:
     /// - _audio_: If the audio output modality is requested, this object contains
-    /// data about the audio response from the model.
-    /// [Learn more from OpenAI](https://platform.openai.com/docs/guides/audio).
+    ///   data about the audio response from the model.
+    ///   [Learn more from OpenAI](https://platform.openai.com/docs/guides/audio).
     #[derive(Debug, Deserialize)]
     pub struct ChatCompletionMessage {
         /// The role of the author of this message. This shall always
@@ -425,12 +1112,39 @@ pub mod no_streaming {
         /// The contents of the message.
         pub content: Option<String>,
         pub reasoning_content: Option<String>,
+        /// The refusal message generated by the model.
+        pub refusal: Option<String>,
         /// The tool calls generated by the model, such as function calls.
-        /// Tool calls deserialization is not supported yet.
         pub tool_calls: Option<Vec<ChatCompletionMessageToolCall>>,
+        /// Annotations for the message, such as the URL citations of a model
+        /// with web search.
+        pub annotations: Option<Vec<Annotation>>,
     }
 
-    #[derive(Debug, Deserialize)]
+    /// An annotation of a message.
+    #[derive(Debug, Deserialize, Clone, PartialEq)]
+    #[serde(tag = "type", rename_all = "snake_case")]
+    pub enum Annotation {
+        /// A URL citation when using web search.
+        UrlCitation { url_citation: UrlCitation },
+        /// An annotation type this crate does not know yet.
+        #[serde(other)]
+        Other,
+    }
+
+    #[derive(Debug, Deserialize, Clone, PartialEq)]
+    pub struct UrlCitation {
+        /// The URL of the web resource.
+        pub url: String,
+        /// The title of the web resource.
+        pub title: String,
+        /// The index of the first character of the URL citation in the message.
+        pub start_index: usize,
+        /// The index of the last character of the URL citation in the message.
+        pub end_index: usize,
+    }
+
+    #[derive(Debug, Deserialize, Clone)]
     #[serde(tag = "type", rename_all = "snake_case")]
     pub enum ChatCompletionMessageToolCall {
         /// The type of the tool. Currently, only `function` is supported.
@@ -439,7 +1153,7 @@ pub mod no_streaming {
             /// The ID of the tool call.
             id: String,
             /// The function that the model called.
-            function: String, // function type
+            function: MessageToolCallFunction,
         },
         /// The type of the tool. Always `custom`.
         /// The field { type = "custom" } is added automatically.
@@ -451,7 +1165,7 @@ pub mod no_streaming {
         },
     }
 
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Clone)]
     pub struct MessageToolCallCustom {
         /// The input for the custom tool call generated by the model.
         pub input: String,
@@ -459,7 +1173,7 @@ pub mod no_streaming {
         pub name: String,
     }
 
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Clone)]
     pub struct MessageToolCallFunction {
         /// The arguments to call the function with, as generated by the model in JSON
         /// format. Note that the model does not always generate valid JSON, and may
@@ -470,14 +1184,33 @@ pub mod no_streaming {
         pub name: String,
     }
 
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Clone, PartialEq)]
     #[serde(rename_all = "snake_case")]
     pub enum ResponseRole {
         /// The role of the response message is always assistant.
         Assistant,
+        /// A role this crate does not know yet.
+        #[serde(untagged)]
+        Other(String),
     }
 
-    #[derive(Debug, Deserialize)]
+    impl ResponseRole {
+        /// Returns the role as sent by the provider.
+        pub fn as_str(&self) -> &str {
+            match self {
+                Self::Assistant => "assistant",
+                Self::Other(role) => role,
+            }
+        }
+    }
+
+    /// Log probability information of a choice, shared by complete responses
+    /// and streamed chunks. A chunk carries the log probabilities of its own
+    /// tokens only.
+    ///
+    /// Providers send `null` or omit the `logprobs` of a choice when they were
+    /// not requested, so it is always an `Option<ChoiceLogprobs>`.
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
     pub struct ChoiceLogprobs {
         /// A list of message content tokens with log probability information.
         pub content: Option<Vec<TokenLogProb>>,
@@ -487,7 +1220,7 @@ pub mod no_streaming {
         pub refusal: Option<Vec<TokenLogProb>>,
     }
 
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
     pub struct TokenLogProb {
         /// The token.
         pub token: String,
@@ -503,27 +1236,28 @@ pub mod no_streaming {
         pub bytes: Option<Vec<u8>>,
         /// List of the most likely tokens and their log probability, at this token
         /// position. In rare cases, there may be fewer than the number of requested
-        /// `top_logprobs` returned.
+        /// `top_logprobs` returned. Empty if the provider omits it.
+        #[serde(default)]
         pub top_logprobs: Vec<TopLogprob>,
     }
 
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
     pub struct TopLogprob {
         /// The token.
         pub token: String,
+        /// The log probability of this token, if it is within the top 20 most likely
+        /// tokens. Otherwise, the value `-9999.0` is used to signify that the token is very
+        /// unlikely.
+        pub logprob: f32,
         /// A list of integers representing the UTF-8 bytes representation of the token.
         ///
         /// Useful in instances where characters are represented by multiple tokens and
         /// their byte representations must be combined to generate the correct text
         /// representation. Can be `null` if there is no bytes representation for the token.
-        pub logprob: f32,
-        /// List of the most likely tokens and their log probability, at this token
-        /// position. In rare cases, there may be fewer than the number of requested
-        /// `top_logprobs` returned.
         pub bytes: Option<Vec<u8>>,
     }
 
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Serialize, Clone)]
     pub struct CompletionUsage {
         /// Number of tokens in the generated completion.
         pub completion_tokens: usize,
@@ -544,7 +1278,7 @@ pub mod no_streaming {
         pub prompt_tokens_details: Option<PromptTokensDetails>,
     }
 
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Serialize, Clone)]
     pub struct CompletionTokensDetails {
         /// When using Predicted Outputs, the number of tokens in the prediction that
         /// appeared in the completion.
@@ -560,7 +1294,7 @@ pub mod no_streaming {
         pub rejected_prediction_tokens: Option<usize>,
     }
 
-    #[derive(Debug, Deserialize)]
+    #[derive(Debug, Deserialize, Serialize, Clone)]
     pub struct PromptTokensDetails {
         /// Audio input tokens present in the prompt.
         pub audio_tokens: Option<usize>,
@@ -568,13 +1302,336 @@ pub mod no_streaming {
         pub cached_tokens: Option<usize>,
     }
 
+    /// The reasoning and the answer of a reasoning model, as returned by
+    /// [`ChatCompletion::reasoned_answer`].
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct ReasonedAnswer {
+        /// The chain of thought, i.e. `reasoning_content`.
+        pub reasoning: Option<String>,
+        /// The final answer, i.e. `content`.
+        pub content: Option<String>,
+    }
+
+    /// How [`ChatCompletion::render`] assembles the output into one string.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct RenderOptions {
+        /// Whether the reasoning is shown before the answer.
+        pub include_reasoning: bool,
+        /// Put before every line of the reasoning, to set it apart from the
+        /// answer. Defaults to `"> "`, a Markdown quote.
+        pub reasoning_prefix: String,
+    }
+
+    impl Default for RenderOptions {
+        fn default() -> Self {
+            Self {
+                include_reasoning: false,
+                reasoning_prefix: "> ".to_string(),
+            }
+        }
+    }
+
+    impl ChatCompletion {
+        /// Whether a choice was stopped by the content filter of the provider,
+        /// i.e. finished with `content_filter`.
+        pub fn was_filtered(&self) -> bool {
+            self.choices
+                .iter()
+                .any(|choice| choice.finish_reason == FinishReason::ContentFilter)
+        }
+
+        /// Renders the output of the choice with index 0 as one string.
+        ///
+        /// The output is the content, or the refusal if there is no content. With
+        /// [`RenderOptions::include_reasoning`], the reasoning comes first, each
+        /// line prefixed with [`RenderOptions::reasoning_prefix`], followed by a
+        /// blank line. A completion without choices renders as an empty string.
+        pub fn render(&self, options: &RenderOptions) -> String {
+            let Some(choice) = self.primary_choice() else {
+                return String::new();
+            };
+            let message = &choice.message;
+            let output = message
+                .content
+                .as_deref()
+                .or(message.refusal.as_deref())
+                .unwrap_or_default();
+
+            let reasoning = message
+                .reasoning_content
+                .as_deref()
+                .map(str::trim)
+                .filter(|reasoning| options.include_reasoning && !reasoning.is_empty());
+            let Some(reasoning) = reasoning else {
+                return output.to_string();
+            };
+
+            let mut rendered = String::new();
+            for line in reasoning.lines() {
+                rendered.push_str(&options.reasoning_prefix);
+                rendered.push_str(line);
+                rendered.push('\n');
+            }
+            if !output.is_empty() {
+                rendered.push('\n');
+                rendered.push_str(output);
+            }
+            rendered
+        }
+
+        /// Returns the reasoning and the answer of the choice with index 0, or
+        /// the first choice if none has index 0, or `None` if there is no
+        /// choice.
+        pub fn reasoned_answer(&self) -> Option<ReasonedAnswer> {
+            self.primary_choice().map(|choice| ReasonedAnswer {
+                reasoning: choice.message.reasoning_content.clone(),
+                content: choice.message.content.clone(),
+            })
+        }
+
+        /// Returns the choices ordered by their `index`. Providers do not always
+        /// return the choices of a request with `n > 1` in order.
+        pub fn sorted_choices(&self) -> Vec<&Choice> {
+            let mut choices: Vec<&Choice> = self.choices.iter().collect();
+            choices.sort_by_key(|choice| choice.index);
+            choices
+        }
+
+        /// Returns the choice whose `index` is `index`, wherever it is in
+        /// `choices`.
+        pub fn choice_by_index(&self, index: usize) -> Option<&Choice> {
+            self.choices.iter().find(|choice| choice.index == index)
+        }
+
+        /// The choice with index 0, or the first choice if none has index 0.
+        fn primary_choice(&self) -> Option<&Choice> {
+            self.choice_by_index(0).or_else(|| self.choices.first())
+        }
+
+        /// Returns the content of every choice, ordered by their `index`, e.g.
+        /// the candidates of a request with `n > 1`.
+        pub fn contents(&self) -> Vec<Option<&str>> {
+            self.sorted_choices()
+                .into_iter()
+                .map(|choice| choice.message.content.as_deref())
+                .collect()
+        }
+
+        /// Returns the text content of the choice with index 0, or the first
+        /// choice if none has index 0.
+        ///
+        /// Fails with [`OapiError::Refusal`] if the model refused to answer,
+        /// and with [`OapiError::EmptyResponse`] if there is no choice or the
+        /// choice has no content, e.g. because it only calls tools.
+        pub fn text(&self) -> Result<&str, OapiError> {
+            let message = &self
+                .primary_choice()
+                .ok_or(OapiError::EmptyResponse)?
+                .message;
+            match (message.content.as_deref(), message.refusal.as_deref()) {
+                (Some(content), _) if !content.is_empty() => Ok(content),
+                (_, Some(refusal)) if !refusal.is_empty() => {
+                    Err(OapiError::Refusal(refusal.to_string()))
+                }
+                _ => Err(OapiError::EmptyResponse),
+            }
+        }
+
+        /// Deserializes the text content, see [`ChatCompletion::text`], into
+        /// `T`, e.g. the answer to a request with a `json_schema`
+        /// [`ResponseFormat`](crate::chat::request::ResponseFormat).
+        ///
+        /// Fails like [`ChatCompletion::text`], and with
+        /// [`OapiError::DeserializationError`] if the content is not valid JSON
+        /// for `T`.
+        pub fn parse_content<T: DeserializeOwned>(&self) -> Result<T, OapiError> {
+            let text = self.text()?;
+            serde_json::from_str(text).map_err(|e| {
+                OapiError::DeserializationError(format!(
+                    "The content is not a valid {}: {}\n{}",
+                    std::any::type_name::<T>(),
+                    e,
+                    text
+                ))
+            })
+        }
+
+        /// Returns the content of the choice with index 0, or the first choice
+        /// if none has index 0, or `None` if there is no choice or it has no
+        /// content.
+        pub fn first_content(&self) -> Option<&str> {
+            self.primary_choice()
+                .and_then(|choice| choice.message.content.as_deref())
+        }
+
+        /// Moves `<think>...</think>` blocks from the content of every choice
+        /// into its `reasoning_content`, for models that reason inline.
+        ///
+        /// See [`ChatCompletion::split_tags`] for the details.
+        pub fn split_think_tags(&mut self) {
+            self.split_tags("think");
+        }
+
+        /// Moves `<tag>...</tag>` blocks from the content of every choice into
+        /// its `reasoning_content`, and leaves the rest in `content`.
+        ///
+        /// Several blocks are joined by newlines and appended to any reasoning
+        /// already present. A closing tag without an opening tag, which some
+        /// models emit when the template inserts the opening tag, makes all the
+        /// text before it reasoning. An unclosed block, usually from a truncated
+        /// response, is reasoning up to the end.
+        pub fn split_tags(&mut self, tag: &str) {
+            for choice in &mut self.choices {
+                let message = &mut choice.message;
+                let Some(content) = message.content.as_deref() else {
+                    continue;
+                };
+                let (reasoning, rest) = split_tagged(content, tag);
+                if reasoning.is_empty() {
+                    continue;
+                }
+
+                let reasoning = reasoning.join("\n");
+                message.reasoning_content = Some(match message.reasoning_content.take() {
+                    Some(existing) if !existing.is_empty() => existing + "\n" + &reasoning,
+                    _ => reasoning,
+                });
+                message.content = Some(rest);
+            }
+        }
+    }
+
+    /// Splits `text` into the trimmed contents of its `<tag>` blocks and the
+    /// remaining text.
+    fn split_tagged(text: &str, tag: &str) -> (Vec<String>, String) {
+        let open = format!("<{}>", tag);
+        let close = format!("</{}>", tag);
+        let mut blocks = Vec::new();
+        let mut rest = String::new();
+        let mut remaining = text;
+
+        if let Some(end) = remaining.find(&close)
+            && !remaining[..end].contains(&open)
+        {
+            blocks.push(remaining[..end].trim().to_string());
+            remaining = &remaining[end + close.len()..];
+        }
+
+        while let Some(start) = remaining.find(&open) {
+            rest.push_str(&remaining[..start]);
+            let inner = &remaining[start + open.len()..];
+            match inner.find(&close) {
+                Some(end) => {
+                    blocks.push(inner[..end].trim().to_string());
+                    remaining = &inner[end + close.len()..];
+                }
+                None => {
+                    blocks.push(inner.trim().to_string());
+                    remaining = "";
+                }
+            }
+        }
+        rest.push_str(remaining);
+
+        (blocks, rest.trim().to_string())
+    }
+
+    impl ChatCompletionMessageToolCall {
+        /// The ID of the tool call, to be answered by the `tool_call_id` of a
+        /// tool message.
+        pub fn id(&self) -> &str {
+            match self {
+                Self::Function { id, .. } | Self::Custom { id, .. } => id,
+            }
+        }
+    }
+
+    /// Prints the message of the choice with index 0: its content, or else its
+    /// refusal, its reasoning, or one `name(arguments)` line per tool call. A
+    /// completion without choices prints nothing.
+    impl std::fmt::Display for ChatCompletion {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let Some(choice) = self.primary_choice() else {
+                return Ok(());
+            };
+            let message = &choice.message;
+            let text = [
+                &message.content,
+                &message.refusal,
+                &message.reasoning_content,
+            ]
+            .into_iter()
+            .flatten()
+            .find(|text| !text.is_empty());
+            if let Some(text) = text {
+                return f.write_str(text);
+            }
+
+            let tool_calls = message.tool_calls.as_deref().unwrap_or_default();
+            for (i, tool_call) in tool_calls.iter().enumerate() {
+                if i > 0 {
+                    writeln!(f)?;
+                }
+                match tool_call {
+                    ChatCompletionMessageToolCall::Function { function, .. } => {
+                        write!(f, "{}({})", function.name, function.arguments)?
+                    }
+                    ChatCompletionMessageToolCall::Custom { custom, .. } => {
+                        write!(f, "{}({})", custom.name, custom.input)?
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl ChatCompletionMessage {
+        /// Converts this message into an assistant message of a request, to
+        /// append it to the conversation before sending the results of its tool
+        /// calls.
+        pub fn to_request_message(&self) -> Message {
+            let tool_calls = self.tool_calls.as_ref().map(|tool_calls| {
+                tool_calls
+                    .iter()
+                    .map(|tool_call| match tool_call {
+                        ChatCompletionMessageToolCall::Function { id, function } => {
+                            AssistantToolCall::Function {
+                                id: id.clone(),
+                                function: ToolCallFunction {
+                                    arguments: function.arguments.clone(),
+                                    name: function.name.clone(),
+                                },
+                            }
+                        }
+                        ChatCompletionMessageToolCall::Custom { id, custom } => {
+                            AssistantToolCall::Custom {
+                                id: id.clone(),
+                                custom: ToolCallCustom {
+                                    input: custom.input.clone(),
+                                    name: custom.name.clone(),
+                                },
+                            }
+                        }
+                    })
+                    .collect()
+            });
+
+            Message::Assistant {
+                content: self.content.clone(),
+                refusal: self.refusal.clone(),
+                name: None,
+                prefix: false,
+                reasoning_content: self.reasoning_content.clone(),
+                tool_calls,
+            }
+        }
+    }
+
     impl FromStr for ChatCompletion {
         type Err = crate::errors::OapiError;
 
         fn from_str(content: &str) -> Result<Self, Self::Err> {
-            let parse_result: Result<ChatCompletion, _> = serde_json::from_str(content)
-                .map_err(|e| OapiError::DeserializationError(e.to_string()));
-            parse_result
+            Ok(serde_json::from_str(content)?)
         }
     }
 
@@ -659,5 +1716,492 @@ pub mod no_streaming {
                 }
             }
         }
+
+        #[test]
+        fn reasoned_answer_deepseek_reasoner() {
+            let json = r#"{
+              "id": "a1b2c3d4-e5f6-7890-abcd-ef1234567890",
+              "object": "chat.completion",
+              "created": 1757944111,
+              "model": "deepseek-reasoner",
+              "choices": [
+                {
+                  "index": 0,
+                  "message": {
+                    "role": "assistant",
+                    "content": "9.11 is smaller than 9.8.",
+                    "reasoning_content": "Compare the tenths digit: 1 is less than 8."
+                  },
+                  "logprobs": null,
+                  "finish_reason": "stop"
+                }
+              ],
+              "usage": {
+                "prompt_tokens": 18,
+                "completion_tokens": 120,
+                "total_tokens": 138,
+                "completion_tokens_details": {
+                  "reasoning_tokens": 98
+                }
+              },
+              "system_fingerprint": "fp_7e0991cad4"
+            }"#;
+
+            let completion = ChatCompletion::from_str(json).unwrap();
+            assert_eq!(
+                completion.reasoned_answer(),
+                Some(ReasonedAnswer {
+                    reasoning: Some("Compare the tenths digit: 1 is less than 8.".to_string()),
+                    content: Some("9.11 is smaller than 9.8.".to_string()),
+                })
+            );
+
+            // Choices out of order are selected by index, as by first_content.
+            let completion = ChatCompletion::from_str(
+                r#"{"id":"1","object":"chat.completion","created":1,"model":"deepseek-reasoner","choices":[{"index":1,"message":{"role":"assistant","content":"B","reasoning_content":"b"},"finish_reason":"stop"},{"index":0,"message":{"role":"assistant","content":"A","reasoning_content":"a"},"finish_reason":"stop"}]}"#,
+            )
+            .unwrap();
+            assert_eq!(
+                completion.reasoned_answer(),
+                Some(ReasonedAnswer {
+                    reasoning: Some("a".to_string()),
+                    content: Some("A".to_string()),
+                })
+            );
+            assert_eq!(completion.first_content(), Some("A"));
+        }
+
+        #[test]
+        fn parse_tool_calls() {
+            let completion = ChatCompletion::from_str(
+                r#"{"id":"1","object":"chat.completion","created":1,"model":"gpt-4o","choices":[{"index":0,"message":{"role":"assistant","content":null,"tool_calls":[{"id":"call_abc","type":"function","function":{"name":"get_weather","arguments":"{\"location\": \"Paris\"}"}},{"id":"call_def","type":"custom","custom":{"name":"run_sql","input":"SELECT 1"}}]},"logprobs":null,"finish_reason":"tool_calls"}]}"#,
+            )
+            .unwrap();
+
+            let tool_calls = completion.choices[0].message.tool_calls.as_ref().unwrap();
+            assert_eq!(tool_calls.len(), 2);
+            assert_eq!(tool_calls[0].id(), "call_abc");
+            match &tool_calls[0] {
+                ChatCompletionMessageToolCall::Function { function, .. } => {
+                    assert_eq!(function.name, "get_weather");
+                    assert_eq!(function.arguments, r#"{"location": "Paris"}"#);
+                }
+                other => panic!("expected a function call, got {:?}", other),
+            }
+            match &tool_calls[1] {
+                ChatCompletionMessageToolCall::Custom { id, custom } => {
+                    assert_eq!(id, "call_def");
+                    assert_eq!(custom.name, "run_sql");
+                    assert_eq!(custom.input, "SELECT 1");
+                }
+                other => panic!("expected a custom call, got {:?}", other),
+            }
+            assert_eq!(
+                completion.to_string(),
+                "get_weather({\"location\": \"Paris\"})\nrun_sql(SELECT 1)"
+            );
+        }
+
+        #[test]
+        fn display_primary_message() {
+            let completion = |choices: &str| {
+                ChatCompletion::from_str(&format!(
+                    r#"{{"id":"1","object":"chat.completion","created":1,"model":"deepseek-reasoner","choices":[{}]}}"#,
+                    choices
+                ))
+                .unwrap()
+                .to_string()
+            };
+            assert_eq!(
+                completion(
+                    r#"{"index":1,"message":{"role":"assistant","content":"Second"},"finish_reason":"stop"},{"index":0,"message":{"role":"assistant","content":"First","reasoning_content":"Hmm."},"finish_reason":"stop"}"#
+                ),
+                "First"
+            );
+            assert_eq!(
+                completion(
+                    r#"{"index":0,"message":{"role":"assistant","content":"","reasoning_content":"Hmm."},"finish_reason":"length"}"#
+                ),
+                "Hmm."
+            );
+            assert_eq!(completion(""), "");
+        }
+
+        #[test]
+        fn parse_url_citations() {
+            let completion = ChatCompletion::from_str(
+                r#"{"id":"1","object":"chat.completion","created":1,"model":"gpt-4o-search-preview","choices":[{"index":0,"message":{"role":"assistant","content":"Rust 1.0 was released in 2015.","annotations":[{"type":"url_citation","url_citation":{"end_index":30,"start_index":0,"title":"Announcing Rust 1.0","url":"https://blog.rust-lang.org/2015/05/15/Rust-1.0.html"}},{"type":"file_citation","file_citation":{"file_id":"file-1"}}]},"finish_reason":"stop"}]}"#,
+            )
+            .unwrap();
+
+            assert_eq!(
+                completion.choices[0].message.annotations.as_deref(),
+                Some(
+                    &[
+                        Annotation::UrlCitation {
+                            url_citation: UrlCitation {
+                                url: "https://blog.rust-lang.org/2015/05/15/Rust-1.0.html"
+                                    .to_string(),
+                                title: "Announcing Rust 1.0".to_string(),
+                                start_index: 0,
+                                end_index: 30,
+                            },
+                        },
+                        Annotation::Other,
+                    ][..]
+                )
+            );
+        }
+
+        #[test]
+        fn text_or_descriptive_error() {
+            let completion = |message: &str| {
+                ChatCompletion::from_str(&format!(
+                    r#"{{"id":"1","object":"chat.completion","created":1,"model":"gpt-4o","choices":[{{"index":0,"message":{},"finish_reason":"stop"}}]}}"#,
+                    message
+                ))
+                .unwrap()
+            };
+
+            let answer = completion(r#"{"role":"assistant","content":"Paris."}"#);
+            assert_eq!(answer.text().unwrap(), "Paris.");
+
+            let refusal =
+                completion(r#"{"role":"assistant","content":null,"refusal":"I can't help."}"#);
+            assert!(matches!(
+                refusal.text(),
+                Err(OapiError::Refusal(message)) if message == "I can't help."
+            ));
+
+            let tool_call = completion(
+                r#"{"role":"assistant","content":"","tool_calls":[{"id":"call_0","type":"function","function":{"name":"get_weather","arguments":"{}"}}]}"#,
+            );
+            assert!(matches!(tool_call.text(), Err(OapiError::EmptyResponse)));
+
+            let empty = ChatCompletion::from_str(
+                r#"{"id":"1","object":"chat.completion","created":1,"model":"gpt-4o","choices":[]}"#,
+            )
+            .unwrap();
+            assert!(matches!(empty.text(), Err(OapiError::EmptyResponse)));
+        }
+
+        #[test]
+        fn parse_structured_content() {
+            #[derive(Deserialize)]
+            struct Capital {
+                city: String,
+                population: u64,
+            }
+
+            let completion = |content: &str| {
+                ChatCompletion::from_str(&format!(
+                    r#"{{"id":"1","object":"chat.completion","created":1,"model":"gpt-4o","choices":[{{"index":0,"message":{{"role":"assistant","content":{}}},"finish_reason":"stop"}}]}}"#,
+                    serde_json::to_string(content).unwrap()
+                ))
+                .unwrap()
+            };
+
+            let capital: Capital = completion(r#"{"city":"Paris","population":2102650}"#)
+                .parse_content()
+                .unwrap();
+            assert_eq!(capital.city, "Paris");
+            assert_eq!(capital.population, 2_102_650);
+
+            assert!(matches!(
+                completion(r#"{"city":"Paris"}"#).parse_content::<Capital>(),
+                Err(OapiError::DeserializationError(_))
+            ));
+            assert!(matches!(
+                completion("").parse_content::<Capital>(),
+                Err(OapiError::EmptyResponse)
+            ));
+        }
+
+        #[test]
+        fn render_with_and_without_reasoning() {
+            let completion = ChatCompletion::from_str(
+                r#"{"id":"1","object":"chat.completion","created":1,"model":"deepseek-reasoner","choices":[{"index":0,"message":{"role":"assistant","content":"9.11 is smaller.","reasoning_content":"Compare the tenths.\n1 is less than 8."},"finish_reason":"stop"}]}"#,
+            )
+            .unwrap();
+
+            assert_eq!(
+                completion.render(&RenderOptions::default()),
+                "9.11 is smaller."
+            );
+            assert_eq!(
+                completion.render(&RenderOptions {
+                    include_reasoning: true,
+                    ..Default::default()
+                }),
+                "> Compare the tenths.\n> 1 is less than 8.\n\n9.11 is smaller."
+            );
+            assert_eq!(
+                completion.render(&RenderOptions {
+                    include_reasoning: true,
+                    reasoning_prefix: "Thinking: ".to_string(),
+                }),
+                "Thinking: Compare the tenths.\nThinking: 1 is less than 8.\n\n9.11 is smaller."
+            );
+
+            let refusal = ChatCompletion::from_str(
+                r#"{"id":"1","object":"chat.completion","created":1,"model":"gpt-4o","choices":[{"index":0,"message":{"role":"assistant","content":null,"refusal":"I can't help with that."},"finish_reason":"stop"}]}"#,
+            )
+            .unwrap();
+            assert_eq!(
+                refusal.render(&RenderOptions {
+                    include_reasoning: true,
+                    ..Default::default()
+                }),
+                "I can't help with that."
+            );
+        }
+
+        #[test]
+        fn unknown_completion_values() {
+            let completion = ChatCompletion::from_str(
+                r#"{"id":"1","object":"gateway.completion","created":1,"model":"deepseek-chat","service_tier":"batch","choices":[{"index":0,"message":{"role":"critic","content":"..."},"logprobs":null,"finish_reason":"paused"}]}"#,
+            )
+            .unwrap();
+
+            assert_eq!(
+                completion.service_tier,
+                Some(ServiceTier::Other("batch".to_string()))
+            );
+            let choice = &completion.choices[0];
+            assert_eq!(
+                choice.finish_reason,
+                FinishReason::Other("paused".to_string())
+            );
+            assert_eq!(choice.finish_reason.as_str(), "paused");
+            assert_eq!(choice.message.role.as_str(), "critic");
+            assert_eq!(FinishReason::ToolCalls.as_str(), "tool_calls");
+            assert_eq!(
+                completion.object,
+                ChatCompletionObject::Other("gateway.completion".to_string())
+            );
+
+            let completion = ChatCompletion::from_str(
+                r#"{"id":"1","created":1,"model":"gateway","choices":[]}"#,
+            )
+            .unwrap();
+            assert_eq!(completion.object, ChatCompletionObject::ChatCompletion);
+        }
+
+        #[test]
+        fn split_think_tags() {
+            let json = r#"{
+              "id": "1",
+              "object": "chat.completion",
+              "created": 1757944111,
+              "model": "qwq-32b",
+              "choices": [
+                {
+                  "index": 0,
+                  "message": {
+                    "role": "assistant",
+                    "content": "<think>\nCompare the tenths digit.\n</think>\n\n9.11 is smaller than 9.8."
+                  },
+                  "logprobs": null,
+                  "finish_reason": "stop"
+                },
+                {
+                  "index": 1,
+                  "message": {
+                    "role": "assistant",
+                    "content": "1 is less than 8.\n</think>\n\n9.11 is smaller."
+                  },
+                  "logprobs": null,
+                  "finish_reason": "stop"
+                },
+                {
+                  "index": 2,
+                  "message": {
+                    "role": "assistant",
+                    "content": "No reasoning here."
+                  },
+                  "logprobs": null,
+                  "finish_reason": "stop"
+                }
+              ]
+            }"#;
+
+            let mut completion = ChatCompletion::from_str(json).unwrap();
+            completion.split_think_tags();
+
+            let message = &completion.choices[0].message;
+            assert_eq!(
+                message.reasoning_content.as_deref(),
+                Some("Compare the tenths digit.")
+            );
+            assert_eq!(
+                message.content.as_deref(),
+                Some("9.11 is smaller than 9.8.")
+            );
+
+            let message = &completion.choices[1].message;
+            assert_eq!(
+                message.reasoning_content.as_deref(),
+                Some("1 is less than 8.")
+            );
+            assert_eq!(message.content.as_deref(), Some("9.11 is smaller."));
+
+            let message = &completion.choices[2].message;
+            assert_eq!(message.reasoning_content, None);
+            assert_eq!(message.content.as_deref(), Some("No reasoning here."));
+
+            let (blocks, rest) =
+                split_tagged("A<reasoning>one</reasoning>B<reasoning>two", "reasoning");
+            assert_eq!(blocks, vec!["one", "two"]);
+            assert_eq!(rest, "AB");
+        }
+
+        #[test]
+        fn completion_logprobs() {
+            let completion = |logprobs: &str| {
+                ChatCompletion::from_str(&format!(
+                    r#"{{"id":"1","object":"chat.completion","created":1,"model":"deepseek-chat","choices":[{{"index":0,"message":{{"role":"assistant","content":"Hi"}},"finish_reason":"stop"{}}}]}}"#,
+                    logprobs
+                ))
+                .unwrap()
+            };
+
+            assert_eq!(completion(r#","logprobs":null"#).choices[0].logprobs, None);
+            assert_eq!(completion("").choices[0].logprobs, None);
+
+            let present = completion(
+                r#","logprobs":{"content":[{"token":"Hi","logprob":-0.5,"bytes":null}],"reasoning_content":null}"#,
+            );
+            let logprobs = present.choices[0].logprobs.as_ref().unwrap();
+            let content = logprobs.content.as_ref().unwrap();
+            assert_eq!(content[0].logprob, -0.5);
+            assert!(content[0].top_logprobs.is_empty());
+            assert_eq!(logprobs.reasoning_content, None);
+        }
+
+        #[test]
+        fn system_fingerprint_string_or_number() {
+            let completion = |fingerprint: &str| {
+                ChatCompletion::from_str(&format!(
+                    r#"{{"id":"1","object":"chat.completion","created":1,"model":"deepseek-chat","choices":[]{}}}"#,
+                    fingerprint
+                ))
+                .unwrap()
+                .system_fingerprint
+            };
+            assert_eq!(
+                completion(r#","system_fingerprint":"fp_7e0991cad4""#).as_deref(),
+                Some("fp_7e0991cad4")
+            );
+            assert_eq!(
+                completion(r#","system_fingerprint":20250101"#).as_deref(),
+                Some("20250101")
+            );
+            assert_eq!(completion(r#","system_fingerprint":null"#), None);
+            assert_eq!(completion(""), None);
+
+            let chunk = super::super::streaming::ChatCompletionChunk::from_str(
+                r#"{"id":"1","choices":[],"created":1,"model":"deepseek-chat","object":"chat.completion.chunk","system_fingerprint":42}"#,
+            )
+            .unwrap();
+            assert_eq!(chunk.system_fingerprint.as_deref(), Some("42"));
+        }
+
+        #[test]
+        fn choices_out_of_order() {
+            let choice = |index: usize| {
+                format!(
+                    r#"{{"index":{},"message":{{"role":"assistant","content":"answer {}"}},"logprobs":null,"finish_reason":"stop"}}"#,
+                    index, index
+                )
+            };
+            let json = format!(
+                r#"{{"id":"1","object":"chat.completion","created":1,"model":"deepseek-chat","choices":[{},{},{}]}}"#,
+                choice(2),
+                choice(0),
+                choice(1)
+            );
+
+            let completion = ChatCompletion::from_str(&json).unwrap();
+            let indices: Vec<usize> = completion
+                .sorted_choices()
+                .iter()
+                .map(|choice| choice.index)
+                .collect();
+            assert_eq!(indices, vec![0, 1, 2]);
+            assert_eq!(
+                completion
+                    .choice_by_index(2)
+                    .and_then(|choice| choice.message.content.as_deref()),
+                Some("answer 2")
+            );
+            assert!(completion.choice_by_index(3).is_none());
+            assert_eq!(
+                completion.contents(),
+                vec![Some("answer 0"), Some("answer 1"), Some("answer 2")]
+            );
+            assert_eq!(completion.first_content(), Some("answer 0"));
+
+            let empty = ChatCompletion::from_str(
+                r#"{"id":"1","object":"chat.completion","created":1,"model":"deepseek-chat","choices":[]}"#,
+            )
+            .unwrap();
+            assert!(empty.contents().is_empty());
+            assert_eq!(empty.first_content(), None);
+
+            let single = ChatCompletion::from_str(&format!(
+                r#"{{"id":"1","object":"chat.completion","created":1,"model":"deepseek-chat","choices":[{}]}}"#,
+                choice(1)
+            ))
+            .unwrap();
+            assert_eq!(single.text().unwrap(), "answer 1");
+            assert_eq!(single.first_content(), Some("answer 1"));
+            assert_eq!(
+                single.reasoned_answer().unwrap().content.as_deref(),
+                Some("answer 1")
+            );
+        }
+
+        #[test]
+        fn tool_calls_to_request_message() {
+            let json = r#"{
+              "id": "5b8f0e9a-2a8c-4d1b-9b3e-6e1f7c2d0a11",
+              "object": "chat.completion",
+              "created": 1757944111,
+              "model": "deepseek-chat",
+              "choices": [
+                {
+                  "index": 0,
+                  "message": {
+                    "role": "assistant",
+                    "content": "",
+                    "reasoning_content": "The user asks for the weather.",
+                    "tool_calls": [
+                      {
+                        "id": "call_0",
+                        "type": "function",
+                        "function": {
+                          "name": "get_weather",
+                          "arguments": "{\"location\":\"Hangzhou\"}"
+                        }
+                      }
+                    ]
+                  },
+                  "logprobs": null,
+                  "finish_reason": "tool_calls"
+                }
+              ]
+            }"#;
+
+            let completion = ChatCompletion::from_str(json).unwrap();
+            let message = completion.choices[0].message.to_request_message();
+            let value = serde_json::to_value(&message).unwrap();
+
+            let original: serde_json::Value = serde_json::from_str(json).unwrap();
+            let original = &original["choices"][0]["message"];
+            assert_eq!(value["role"], "assistant");
+            assert_eq!(value["content"], original["content"]);
+            assert_eq!(value["reasoning_content"], original["reasoning_content"]);
+            assert_eq!(value["tool_calls"], original["tool_calls"]);
+        }
     }
 }
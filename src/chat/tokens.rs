@@ -0,0 +1,197 @@
+//! Estimating the prompt tokens of a chat request locally, before sending it.
+//! Enabled by the `tokenizer` feature.
+//!
+//! [`RequestBody::estimate_prompt_tokens`] encodes every message with the
+//! tiktoken encoding of the model and adds the framing overhead OpenAI
+//! documents for its chat models: 3 tokens per message, 1 more for a `name`
+//! or a tool call, and 3 to prime the reply. Compare the result with the
+//! context window from [`known_limits`](super::model_limits::known_limits) to
+//! budget `max_completion_tokens` or catch an oversized prompt.
+//!
+//! The count is exact only for OpenAI models. Other models, and models the
+//! encoding is not known for, are counted with `o200k_base`, which is close for
+//! most providers but not the tokenizer they use. Images, tool definitions and
+//! the response format are not counted.
+
+use tiktoken_rs::{
+    CoreBPE, bpe_for_tokenizer,
+    tokenizer::{Tokenizer, get_tokenizer},
+};
+
+use super::request::{AssistantToolCall, Content, ContentPart, Message, RequestBody};
+
+/// Tokens framing every message.
+const TOKENS_PER_MESSAGE: usize = 3;
+/// Extra tokens of a message with a `name`.
+const TOKENS_PER_NAME: usize = 1;
+/// Extra tokens of every tool call of an assistant message.
+const TOKENS_PER_TOOL_CALL: usize = 1;
+/// Tokens priming the assistant reply, added once per request.
+const REPLY_PRIMING: usize = 3;
+
+/// Counts the tokens of `text` in the encoding of `model`, without any message
+/// overhead.
+pub fn count_tokens(model: &str, text: &str) -> usize {
+    encoding(model).count_with_special_tokens(text)
+}
+
+/// The encoding of a chat model, `o200k_base` for unknown models and encodings
+/// of older completion models.
+fn encoding(model: &str) -> &'static CoreBPE {
+    let tokenizer = match get_tokenizer(model) {
+        Some(
+            tokenizer @ (Tokenizer::Cl100kBase | Tokenizer::O200kBase | Tokenizer::O200kHarmony),
+        ) => tokenizer,
+        _ => Tokenizer::O200kBase,
+    };
+    bpe_for_tokenizer(tokenizer).expect("chat encodings are built into tiktoken-rs")
+}
+
+impl RequestBody {
+    /// Estimates the number of prompt tokens of the messages, see the
+    /// [module documentation](self) for what is counted.
+    pub fn estimate_prompt_tokens(&self) -> usize {
+        let bpe = encoding(&self.model);
+        let count = |text: &str| bpe.count_with_special_tokens(text);
+
+        let mut tokens = REPLY_PRIMING;
+        for message in &self.messages {
+            tokens += TOKENS_PER_MESSAGE;
+            let (role, name) = match message {
+                Message::System { content, name } => {
+                    tokens += count(content);
+                    ("system", name.as_deref())
+                }
+                Message::Developer { content, name } => {
+                    tokens += count(content);
+                    ("developer", name.as_deref())
+                }
+                Message::User { content, name } => {
+                    tokens += content_tokens(content, &count);
+                    ("user", name.as_deref())
+                }
+                Message::Assistant {
+                    content,
+                    refusal,
+                    name,
+                    reasoning_content,
+                    tool_calls,
+                    ..
+                } => {
+                    for text in [content, refusal, reasoning_content].into_iter().flatten() {
+                        tokens += count(text);
+                    }
+                    for tool_call in tool_calls.iter().flatten() {
+                        let (name, input) = match tool_call {
+                            AssistantToolCall::Function { function, .. } => {
+                                (&function.name, &function.arguments)
+                            }
+                            AssistantToolCall::Custom { custom, .. } => {
+                                (&custom.name, &custom.input)
+                            }
+                        };
+                        tokens += count(name) + count(input) + TOKENS_PER_TOOL_CALL;
+                    }
+                    ("assistant", name.as_deref())
+                }
+                Message::Tool { content, .. } => {
+                    tokens += content_tokens(content, &count);
+                    ("tool", None)
+                }
+                Message::Function { content, name } => {
+                    tokens += count(content);
+                    ("function", Some(name.as_str()))
+                }
+            };
+            tokens += count(role);
+            if let Some(name) = name {
+                tokens += count(name) + TOKENS_PER_NAME;
+            }
+        }
+        tokens
+    }
+}
+
+/// The tokens of the text of `content`. Image parts are not counted.
+fn content_tokens(content: &Content, count: &impl Fn(&str) -> usize) -> usize {
+    match content {
+        Content::Text(text) => count(text),
+        Content::Parts(parts) => parts
+            .iter()
+            .map(|part| match part {
+                ContentPart::Text { text } => count(text),
+                ContentPart::ImageUrl { .. } => 0,
+            })
+            .sum(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tiktoken_rs::{ChatCompletionRequestMessage, num_tokens_from_messages};
+
+    use super::*;
+
+    #[test]
+    fn matches_tiktoken() {
+        let request = RequestBody {
+            messages: vec![
+                Message::system("You are a helpful assistant that only speaks French."),
+                Message::User {
+                    content: "Hello, how are you?".into(),
+                    name: Some("alice".to_string()),
+                },
+                Message::assistant("Bonjour ! Je vais bien, merci."),
+            ],
+            model: "gpt-4o".to_string(),
+            ..Default::default()
+        };
+        let messages = [
+            (
+                "system",
+                "You are a helpful assistant that only speaks French.",
+                None,
+            ),
+            ("user", "Hello, how are you?", Some("alice")),
+            ("assistant", "Bonjour ! Je vais bien, merci.", None),
+        ]
+        .map(|(role, content, name)| ChatCompletionRequestMessage {
+            role: role.to_string(),
+            content: Some(content.to_string()),
+            name: name.map(str::to_string),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            request.estimate_prompt_tokens(),
+            num_tokens_from_messages("gpt-4o", &messages).unwrap()
+        );
+    }
+
+    #[test]
+    fn unknown_model_and_parts() {
+        let request = RequestBody {
+            messages: vec![Message::user(vec![
+                ContentPart::Text {
+                    text: "What is in this image?".to_string(),
+                },
+                ContentPart::ImageUrl {
+                    image_url: crate::chat::request::ImageUrl {
+                        url: "https://example.com/cat.png".to_string(),
+                        detail: None,
+                    },
+                },
+            ])],
+            model: "deepseek-chat".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            request.estimate_prompt_tokens(),
+            REPLY_PRIMING
+                + TOKENS_PER_MESSAGE
+                + count_tokens("gpt-4o", "user")
+                + count_tokens("gpt-4o", "What is in this image?")
+        );
+    }
+}
@@ -19,10 +19,10 @@ mod tests {
 
     const QWEN_MODEL: &str = "qwen-coder-turbo-latest";
     const QWEN_URL: &str = "https://dashscope.aliyuncs.com/compatible-mode/v1/completions";
-    const QWEN_API_KEY: LazyLock<&'static str> =
+    static QWEN_API_KEY: LazyLock<&str> =
         LazyLock::new(|| include_str!("../../keys/modelstudio_domestic_key").trim());
 
-    const REQUEST_BODY: LazyLock<request::CompletionRequest> =
+    static REQUEST_BODY: LazyLock<request::CompletionRequest> =
         LazyLock::new(|| request::CompletionRequest {
             model: QWEN_MODEL.to_string(),
             prompt: request::Prompt::PromptString(
@@ -2,6 +2,8 @@ use std::collections::HashMap;
 
 use serde::Serialize;
 
+pub use crate::chat::request::StopKeywords;
+use crate::errors::OapiError;
 use crate::rest::post::{NoStream, Post, Stream};
 
 #[derive(Debug, Serialize, Default, Clone)]
@@ -18,7 +20,9 @@ pub struct CompletionRequest {
     /// the highest log probability per token). Results cannot be streamed.
     ///
     /// When used with `n`, `best_of` controls the number of candidate completions and
-    /// `n` specifies how many to return – `best_of` must be greater than `n`.
+    /// `n` specifies how many to return – `best_of` must be at least `n`. Most
+    /// providers reject `best_of` greater than 1 in a streaming request; see
+    /// [`CompletionRequest::validate`].
     ///
     /// **Note:** Because this parameter generates many completions, it can quickly
     /// consume your token quota. Use carefully and ensure that you have reasonable
@@ -92,7 +96,8 @@ pub struct CompletionRequest {
     /// returned text will not contain the stop sequence.
     ///
     /// Note: Not supported with latest reasoning models `o3` and `o4-mini`.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// An empty list is not sent.
+    #[serde(skip_serializing_if = "StopKeywords::is_unset")]
     pub stop: Option<StopKeywords>,
     /// Whether to stream back partial progress. If set, tokens will be sent as
     /// data-only
@@ -106,7 +111,10 @@ pub struct CompletionRequest {
     pub stream_options: Option<StreamOptions>,
     /// The suffix that comes after a completion of inserted text.
     ///
-    /// This parameter is only supported for `gpt-3.5-turbo-instruct`.
+    /// With `prompt` as the text before the insertion point, this asks for the
+    /// code in between, as in fill-in-the-middle. OpenAI only supports it for
+    /// `gpt-3.5-turbo-instruct`; code models of other providers, such as Qwen
+    /// coder models, support it too.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub suffix: Option<String>,
     /// What sampling temperature to use, between 0 and 2. Higher values like 0.8 will
@@ -130,6 +138,7 @@ pub struct CompletionRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<String>,
     /// Add additional JSON properties to the request
+    #[serde(flatten)]
     pub extra_body: serde_json::Map<String, serde_json::Value>,
 }
 
@@ -173,17 +182,53 @@ pub struct StreamOptions {
     pub include_usage: bool,
 }
 
-#[derive(Debug, Serialize, Clone)]
-#[serde(untagged)]
-pub enum StopKeywords {
-    Word(String),
-    Words(Vec<String>),
+impl CompletionRequest {
+    /// Checks the request locally for parameters the provider would reject.
+    ///
+    /// - `best_of` must be at least `n`, since `n` completions are picked from
+    ///   the `best_of` candidates. An unset `n` counts as 1.
+    /// - `best_of` greater than 1 cannot be streamed, since the best candidate
+    ///   is only known once all of them are complete.
+    /// - `logprobs` must be at most 5.
+    pub fn validate(&self) -> Result<(), OapiError> {
+        if let Some(logprobs) = self.logprobs
+            && logprobs > 5
+        {
+            return Err(OapiError::InvalidParameter(format!(
+                "`logprobs` is {}, but must be at most 5",
+                logprobs
+            )));
+        }
+
+        let Some(best_of) = self.best_of else {
+            return Ok(());
+        };
+
+        let n = self.n.unwrap_or(1);
+        if best_of < n {
+            return Err(OapiError::InvalidParameter(format!(
+                "`best_of` is {}, but must be at least `n`, which is {}",
+                best_of, n
+            )));
+        }
+        if self.stream && best_of > 1 {
+            return Err(OapiError::InvalidParameter(format!(
+                "`best_of` is {}, but a streaming request requires at most 1",
+                best_of
+            )));
+        }
+        Ok(())
+    }
 }
 
 impl Post for CompletionRequest {
     fn is_streaming(&self) -> bool {
         self.stream
     }
+
+    fn path(&self) -> Option<String> {
+        Some("/completions".to_string())
+    }
 }
 
 impl NoStream for CompletionRequest {
@@ -204,9 +249,143 @@ mod tests {
 
     const QWEN_MODEL: &str = "qwen-coder-turbo-latest";
     const QWEN_URL: &str = "https://dashscope.aliyuncs.com/compatible-mode/v1/completions";
-    const QWEN_API_KEY: LazyLock<&'static str> =
+    static QWEN_API_KEY: LazyLock<&str> =
         LazyLock::new(|| include_str!("../../keys/modelstudio_domestic_key").trim());
 
+    #[test]
+    fn serialize_sampling_parameters() {
+        let request = CompletionRequest {
+            model: QWEN_MODEL.to_string(),
+            prompt: Prompt::PromptString("Once upon a time".to_string()),
+            max_tokens: Some(16),
+            ..Default::default()
+        };
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "model": QWEN_MODEL,
+                "prompt": "Once upon a time",
+                "max_tokens": 16,
+                "stream": false,
+            })
+        );
+
+        let mut extra_body = serde_json::Map::new();
+        extra_body.insert("enable_thinking".to_string(), false.into());
+        let request = CompletionRequest {
+            temperature: Some(0.5),
+            top_p: Some(0.25),
+            frequency_penalty: Some(-1.0),
+            presence_penalty: Some(1.5),
+            stop: Some(StopKeywords::Words(vec![
+                "\n".to_string(),
+                "END".to_string(),
+            ])),
+            extra_body,
+            ..request
+        };
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(value["temperature"], 0.5);
+        assert_eq!(value["top_p"], 0.25);
+        assert_eq!(value["frequency_penalty"], -1.0);
+        assert_eq!(value["presence_penalty"], 1.5);
+        assert_eq!(value["stop"], serde_json::json!(["\n", "END"]));
+        assert_eq!(value["enable_thinking"], false);
+
+        let request = CompletionRequest {
+            stop: Some(StopKeywords::Word("END".to_string())),
+            ..request
+        };
+        assert_eq!(serde_json::to_value(&request).unwrap()["stop"], "END");
+
+        let request = CompletionRequest {
+            stop: Some(StopKeywords::Words(Vec::new())),
+            ..request
+        };
+        assert!(
+            serde_json::to_value(&request)
+                .unwrap()
+                .get("stop")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn serialize_fill_in_the_middle() {
+        let request = CompletionRequest {
+            model: QWEN_MODEL.to_string(),
+            prompt: Prompt::PromptString("def fib(n):\n".to_string()),
+            suffix: Some("\nprint(fib(10))\n".to_string()),
+            echo: Some(false),
+            ..Default::default()
+        };
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(value["suffix"], "\nprint(fib(10))\n");
+        assert_eq!(value["echo"], false);
+    }
+
+    #[test]
+    fn validate_best_of() {
+        let request = CompletionRequest {
+            model: QWEN_MODEL.to_string(),
+            best_of: Some(3),
+            n: Some(2),
+            ..Default::default()
+        };
+        assert!(request.validate().is_ok());
+        assert!(CompletionRequest::default().validate().is_ok());
+
+        let fewer_than_n = CompletionRequest {
+            n: Some(4),
+            ..request.clone()
+        };
+        assert!(matches!(
+            fewer_than_n.validate(),
+            Err(OapiError::InvalidParameter(_))
+        ));
+
+        let streaming = CompletionRequest {
+            stream: true,
+            ..request.clone()
+        };
+        assert!(matches!(
+            streaming.validate(),
+            Err(OapiError::InvalidParameter(_))
+        ));
+
+        let streaming_single = CompletionRequest {
+            best_of: Some(1),
+            n: None,
+            stream: true,
+            ..request
+        };
+        assert!(streaming_single.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_logprobs() {
+        let request = CompletionRequest {
+            model: QWEN_MODEL.to_string(),
+            logprobs: Some(5),
+            ..Default::default()
+        };
+        assert!(request.validate().is_ok());
+        assert_eq!(
+            serde_json::to_value(&request).unwrap()["logprobs"],
+            serde_json::json!(5)
+        );
+
+        let too_many = CompletionRequest {
+            logprobs: Some(6),
+            ..request
+        };
+        assert!(matches!(
+            too_many.validate(),
+            Err(OapiError::InvalidParameter(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_qwen_completions_no_stream() -> Result<(), anyhow::Error> {
         let request_body = CompletionRequest {
@@ -1,4 +1,7 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
 
 use serde::Deserialize;
 
@@ -21,21 +24,28 @@ pub struct Completion {
     ///
     /// Can be used in conjunction with the `seed` request parameter to understand when
     /// backend changes have been made that might impact determinism.
+    #[serde(default, deserialize_with = "crate::de::string_or_number")]
     pub system_fingerprint: Option<String>,
     /// Usage statistics for the completion request.
     pub usage: Option<CompletionUsage>,
 }
 
+/// The log probabilities of a choice, returned when the request sets
+/// `logprobs`. The lists are parallel: entry `i` of each describes token `i`.
 #[derive(Debug, Deserialize, Clone)]
 pub struct Logprobs {
     /// The offset into the generated text for each token.
     pub text_offset: Option<Vec<usize>>,
     /// The log probability of each token in the generated text.
-    pub token_logprobs: Option<Vec<f32>>,
+    ///
+    /// `None` for the first token of an echoed prompt, which has no preceding
+    /// context to be predicted from.
+    pub token_logprobs: Option<Vec<Option<f32>>>,
     /// The tokens generated by the model.
     pub tokens: Option<Vec<String>>,
-    /// The top log probabilities for each token position.
-    pub top_logprobs: Option<Vec<HashMap<String, f32>>>,
+    /// The top log probabilities for each token position, with up to
+    /// `logprobs` alternatives each. `None` where `token_logprobs` is `None`.
+    pub top_logprobs: Option<Vec<Option<HashMap<String, f32>>>>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -100,8 +110,190 @@ impl FromStr for Completion {
     type Err = OapiError;
 
     fn from_str(content: &str) -> Result<Self, Self::Err> {
-        let parse_result: Result<Self, _> = serde_json::from_str(content)
-            .map_err(|e| OapiError::DeserializationError(e.to_string()));
-        parse_result
+        Ok(serde_json::from_str(content)?)
+    }
+}
+
+/// Folds streamed completion chunks into the full [`Completion`].
+///
+/// The text of each choice is concatenated by index, and the last
+/// `finish_reason`, `system_fingerprint` and usage seen are kept. Log
+/// probabilities are not accumulated.
+#[derive(Debug, Default, Clone)]
+pub struct CompletionStreamAccumulator {
+    id: Option<String>,
+    created: usize,
+    model: String,
+    object: String,
+    system_fingerprint: Option<String>,
+    usage: Option<CompletionUsage>,
+    choices: BTreeMap<usize, AccumulatedChoice>,
+}
+
+#[derive(Debug, Default, Clone)]
+struct AccumulatedChoice {
+    text: String,
+    finish_reason: Option<String>,
+}
+
+impl CompletionStreamAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Folds a chunk into the accumulated completion, returning the text it
+    /// added to each choice, by index.
+    pub fn push(&mut self, chunk: &Completion) -> Vec<(usize, String)> {
+        if self.id.is_none() {
+            self.id = Some(chunk.id.clone());
+            self.created = chunk.created;
+            self.model = chunk.model.clone();
+            self.object = chunk.object.clone();
+        }
+        if chunk.system_fingerprint.is_some() {
+            self.system_fingerprint = chunk.system_fingerprint.clone();
+        }
+        if chunk.usage.is_some() {
+            self.usage = chunk.usage.clone();
+        }
+
+        let mut deltas = Vec::new();
+        for choice in &chunk.choices {
+            let accumulated = self.choices.entry(choice.index).or_default();
+            if !choice.text.is_empty() {
+                accumulated.text.push_str(&choice.text);
+                deltas.push((choice.index, choice.text.clone()));
+            }
+            if choice.finish_reason.is_some() {
+                accumulated.finish_reason = choice.finish_reason.clone();
+            }
+        }
+        deltas
+    }
+
+    /// Whether every choice received its `finish_reason`.
+    pub fn is_complete(&self) -> bool {
+        !self.choices.is_empty()
+            && self
+                .choices
+                .values()
+                .all(|choice| choice.finish_reason.is_some())
+    }
+
+    /// Builds the full completion from the chunks pushed so far.
+    ///
+    /// Fails if no chunk was pushed, or if a choice never received its
+    /// `finish_reason`, which means the stream was cut off.
+    pub fn finish(self) -> Result<Completion, OapiError> {
+        let id = self
+            .id
+            .ok_or_else(|| OapiError::StreamError("No chunk was received".to_string()))?;
+
+        let choices = self
+            .choices
+            .into_iter()
+            .map(|(index, choice)| {
+                if choice.finish_reason.is_none() {
+                    return Err(OapiError::StreamError(format!(
+                        "The stream ended before choice {} finished",
+                        index
+                    )));
+                }
+                Ok(CompletionChoice {
+                    finish_reason: choice.finish_reason,
+                    index,
+                    logprobs: None,
+                    text: choice.text,
+                })
+            })
+            .collect::<Result<Vec<_>, OapiError>>()?;
+
+        Ok(Completion {
+            id,
+            choices,
+            created: self.created,
+            model: self.model,
+            object: self.object,
+            system_fingerprint: self.system_fingerprint,
+            usage: self.usage,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_without_usage() {
+        let completion = Completion::from_str(
+            r#"{"id":"cmpl-1","object":"text_completion","created":1718345013,"model":"qwen-coder-turbo-latest","choices":[{"text":"Hello","index":0,"logprobs":null,"finish_reason":"stop"}]}"#,
+        )
+        .unwrap();
+        assert!(completion.usage.is_none());
+        assert!(completion.system_fingerprint.is_none());
+        assert_eq!(completion.choices[0].text, "Hello");
+    }
+
+    #[test]
+    fn parse_echoed_logprobs() {
+        let completion = Completion::from_str(
+            r#"{"id":"cmpl-1","object":"text_completion","created":1718345013,"model":"gpt-3.5-turbo-instruct","choices":[{"text":"Say this is a test","index":0,"logprobs":{"tokens":["Say"," this"," is"],"token_logprobs":[null,-3.21,-0.05],"top_logprobs":[null,{" this":-3.21," it":-1.4},{" is":-0.05," was":-3.3}],"text_offset":[0,3,8]},"finish_reason":"length"}]}"#,
+        )
+        .unwrap();
+
+        let logprobs = completion.choices[0].logprobs.as_ref().unwrap();
+        assert_eq!(logprobs.tokens.as_ref().unwrap()[1], " this");
+        assert_eq!(
+            logprobs.token_logprobs.as_deref(),
+            Some(&[None, Some(-3.21), Some(-0.05)][..])
+        );
+        let top_logprobs = logprobs.top_logprobs.as_ref().unwrap();
+        assert!(top_logprobs[0].is_none());
+        assert_eq!(top_logprobs[2].as_ref().unwrap()[" was"], -3.3);
+        assert_eq!(logprobs.text_offset.as_deref(), Some(&[0, 3, 8][..]));
+    }
+
+    #[test]
+    fn numeric_system_fingerprint() {
+        let completion = Completion::from_str(
+            r#"{"id":"cmpl-1","object":"text_completion","created":1718345013,"model":"qwen-coder-turbo-latest","system_fingerprint":1234,"choices":[]}"#,
+        )
+        .unwrap();
+        assert_eq!(completion.system_fingerprint.as_deref(), Some("1234"));
+    }
+
+    #[test]
+    fn accumulate_qwen_stream() {
+        let chunks = [
+            r#"{"id":"cmpl-7a1b","choices":[{"finish_reason":null,"index":0,"logprobs":null,"text":"    body, err"}],"created":1735113344,"model":"qwen-coder-turbo-latest","object":"text_completion","system_fingerprint":null,"usage":null}"#,
+            r#"{"id":"cmpl-7a1b","choices":[{"finish_reason":null,"index":0,"logprobs":null,"text":" := ioutil.ReadAll"}],"created":1735113344,"model":"qwen-coder-turbo-latest","object":"text_completion","system_fingerprint":null,"usage":null}"#,
+            r#"{"id":"cmpl-7a1b","choices":[{"finish_reason":"stop","index":0,"logprobs":null,"text":"(res.Body)"}],"created":1735113344,"model":"qwen-coder-turbo-latest","object":"text_completion","system_fingerprint":null,"usage":null}"#,
+            r#"{"id":"cmpl-7a1b","choices":[],"created":1735113344,"model":"qwen-coder-turbo-latest","object":"text_completion","system_fingerprint":null,"usage":{"prompt_tokens":312,"completion_tokens":9,"total_tokens":321}}"#,
+        ];
+
+        let mut accumulator = CompletionStreamAccumulator::new();
+        let first = accumulator.push(&Completion::from_str(chunks[0]).unwrap());
+        assert_eq!(first, vec![(0, "    body, err".to_string())]);
+        for chunk in &chunks[1..3] {
+            accumulator.push(&Completion::from_str(chunk).unwrap());
+        }
+        assert!(accumulator.is_complete());
+        accumulator.push(&Completion::from_str(chunks[3]).unwrap());
+
+        let completion = accumulator.finish().unwrap();
+        assert_eq!(completion.id, "cmpl-7a1b");
+        assert_eq!(completion.choices.len(), 1);
+        assert_eq!(
+            completion.choices[0].text,
+            "    body, err := ioutil.ReadAll(res.Body)"
+        );
+        assert_eq!(completion.choices[0].finish_reason.as_deref(), Some("stop"));
+        assert_eq!(completion.usage.unwrap().total_tokens, 321);
+
+        let mut cut_off = CompletionStreamAccumulator::new();
+        cut_off.push(&Completion::from_str(chunks[0]).unwrap());
+        assert!(!cut_off.is_complete());
+        assert!(matches!(cut_off.finish(), Err(OapiError::StreamError(_))));
     }
 }
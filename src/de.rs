@@ -0,0 +1,26 @@
+//! Lenient deserializers for fields whose JSON type varies across providers.
+
+use serde::{Deserialize, Deserializer};
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StringOrNumber {
+    String(String),
+    Number(serde_json::Number),
+}
+
+/// Deserializes a string, or a number as its decimal string. `null` becomes
+/// `None`; pair it with `#[serde(default)]` so a missing field does too.
+///
+/// Some gateways send `system_fingerprint` as a number.
+pub(crate) fn string_or_number<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(
+        Option::<StringOrNumber>::deserialize(deserializer)?.map(|value| match value {
+            StringOrNumber::String(string) => string,
+            StringOrNumber::Number(number) => number.to_string(),
+        }),
+    )
+}
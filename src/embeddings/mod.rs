@@ -0,0 +1,34 @@
+//! Creates an embedding vector representing the input text.
+//!
+//! Embeddings can be stored in a vector database and compared with each other,
+//! which is the usual way to retrieve relevant documents for a prompt.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use openai_interface::embeddings::request::{EmbeddingInput, EmbeddingRequest};
+//! use openai_interface::rest::post::NoStream;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let request = EmbeddingRequest {
+//!         model: "text-embedding-3-small".to_string(),
+//!         input: EmbeddingInput::Strings(vec![
+//!             "The food was delicious.".to_string(),
+//!             "The waiter was friendly.".to_string(),
+//!         ]),
+//!         ..Default::default()
+//!     };
+//!
+//!     let response = request
+//!         .get_response("https://api.openai.com/v1/embeddings", "sk-...")
+//!         .await?;
+//!     for embedding in &response.data {
+//!         println!("{}: {} dimensions", embedding.index, embedding.embedding.len());
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+pub mod request;
+pub mod response;
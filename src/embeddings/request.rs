@@ -0,0 +1,109 @@
+use serde::Serialize;
+
+use crate::rest::post::{NoStream, Post};
+
+#[derive(Debug, Serialize, Default, Clone)]
+pub struct EmbeddingRequest {
+    /// ID of the model to use.
+    pub model: String,
+    /// Input text to embed, encoded as a string or array of strings. The input
+    /// must not exceed the max input tokens of the model, and cannot be an empty
+    /// string.
+    pub input: EmbeddingInput,
+    /// The format to return the embeddings in. Either `float` or `base64`.
+    ///
+    /// Both are decoded into [`Embedding::embedding`](super::response::Embedding::embedding);
+    /// `base64` makes the response several times smaller.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding_format: Option<EncodingFormat>,
+    /// The number of dimensions the resulting output embeddings should have.
+    /// Only supported in `text-embedding-3` and later models.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<usize>,
+    /// A unique identifier representing your end-user, which can help OpenAI to monitor
+    /// and detect abuse.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// Add additional JSON properties to the request
+    #[serde(flatten)]
+    pub extra_body: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(untagged)]
+pub enum EmbeddingInput {
+    /// String
+    String(String),
+    /// Array of strings, embedded in one request
+    Strings(Vec<String>),
+}
+
+impl Default for EmbeddingInput {
+    fn default() -> Self {
+        Self::String("".to_string())
+    }
+}
+
+impl From<String> for EmbeddingInput {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&str> for EmbeddingInput {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<Vec<String>> for EmbeddingInput {
+    fn from(value: Vec<String>) -> Self {
+        Self::Strings(value)
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EncodingFormat {
+    Float,
+    Base64,
+}
+
+impl Post for EmbeddingRequest {
+    fn is_streaming(&self) -> bool {
+        false
+    }
+
+    fn path(&self) -> Option<String> {
+        Some("/embeddings".to_string())
+    }
+}
+
+impl NoStream for EmbeddingRequest {
+    type Response = super::response::EmbeddingResponse;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_request() {
+        let request = EmbeddingRequest {
+            model: "text-embedding-3-small".to_string(),
+            input: vec!["a".to_string(), "b".to_string()].into(),
+            encoding_format: Some(EncodingFormat::Base64),
+            dimensions: Some(256),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "model": "text-embedding-3-small",
+                "input": ["a", "b"],
+                "encoding_format": "base64",
+                "dimensions": 256,
+            })
+        );
+    }
+}
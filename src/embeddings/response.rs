@@ -0,0 +1,113 @@
+use std::str::FromStr;
+
+use base64::Engine;
+use serde::{Deserialize, Deserializer};
+
+use crate::errors::OapiError;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct EmbeddingResponse {
+    /// The list of embeddings generated by the model, one per input.
+    pub data: Vec<Embedding>,
+    /// The name of the model used to generate the embedding.
+    pub model: String,
+    /// The object type, which is always "list".
+    pub object: String,
+    /// The usage information for the request.
+    pub usage: Option<EmbeddingUsage>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Embedding {
+    /// The index of the input this embedding belongs to.
+    pub index: usize,
+    /// The embedding vector. A `base64` response is decoded into the same
+    /// floats as a `float` one.
+    #[serde(deserialize_with = "floats_or_base64")]
+    pub embedding: Vec<f32>,
+    /// The object type, which is always "embedding".
+    pub object: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct EmbeddingUsage {
+    /// The number of tokens used by the prompt.
+    pub prompt_tokens: usize,
+    /// The total number of tokens used by the request.
+    pub total_tokens: usize,
+}
+
+impl FromStr for EmbeddingResponse {
+    type Err = OapiError;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(content).map_err(OapiError::from)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FloatsOrBase64 {
+    Floats(Vec<f32>),
+    Base64(String),
+}
+
+/// Deserializes an array of floats, or a base64 string of little-endian `f32`s
+/// as sent for `encoding_format: "base64"`.
+fn floats_or_base64<'de, D>(deserializer: D) -> Result<Vec<f32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match FloatsOrBase64::deserialize(deserializer)? {
+        FloatsOrBase64::Floats(floats) => Ok(floats),
+        FloatsOrBase64::Base64(encoded) => {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .map_err(serde::de::Error::custom)?;
+            if bytes.len() % 4 != 0 {
+                return Err(serde::de::Error::custom(format!(
+                    "base64 embedding of {} bytes is not a sequence of f32",
+                    bytes.len()
+                )));
+            }
+            Ok(bytes
+                .chunks_exact(4)
+                .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn float_and_base64_embeddings() {
+        let floats = [0.5f32, -1.25, 3.0];
+        let encoded = base64::engine::general_purpose::STANDARD.encode(
+            floats
+                .iter()
+                .flat_map(|f| f.to_le_bytes())
+                .collect::<Vec<u8>>(),
+        );
+        let json = format!(
+            r#"{{"object":"list","model":"text-embedding-3-small","data":[
+                {{"object":"embedding","index":0,"embedding":[0.5,-1.25,3.0]}},
+                {{"object":"embedding","index":1,"embedding":"{}"}}
+            ],"usage":{{"prompt_tokens":4,"total_tokens":4}}}}"#,
+            encoded
+        );
+
+        let response = EmbeddingResponse::from_str(&json).unwrap();
+        assert_eq!(response.data[0].embedding, floats);
+        assert_eq!(response.data[1].embedding, floats);
+        assert_eq!(response.usage.unwrap().total_tokens, 4);
+
+        let truncated = r#"{"object":"list","model":"m","data":[{"object":"embedding","index":0,"embedding":"AAAA"}]}"#;
+        assert!(matches!(
+            EmbeddingResponse::from_str(truncated),
+            Err(OapiError::DeserializationError(_))
+        ));
+    }
+}
@@ -1,17 +1,35 @@
-use std::path::PathBuf;
+use std::{fmt, path::PathBuf, time::Duration};
 
+use serde::Deserialize;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum OapiError {
     #[error("Failed to send request: {0}")]
     SendError(String),
+    /// No connection to the provider could be opened, e.g. because DNS
+    /// resolution or the TLS handshake failed. The request was not sent.
+    #[error("Failed to connect: {0}")]
+    Connect(String),
+    /// The response body could not be decoded, e.g. because it is not valid
+    /// text in its charset.
+    #[error("Failed to decode the response: {0}")]
+    Decode(String),
     #[error("Response error: {0}")]
     ResponseError(String),
     #[error("Invalid response code: {0}")]
     ResponseCode(u16),
     #[error("Invalid response status: {0}")]
     ResponseStatus(u16),
+    /// The provider answered with an error status. The reason is parsed from the
+    /// response body, or the body is kept whole if it is not an error object.
+    ///
+    /// This is the HTTP error variant: every error status of a request that
+    /// reached the provider ends up here, with its status and body, unlike
+    /// [`OapiError::Connect`] and [`OapiError::Timeout`]. Boxed to keep
+    /// `OapiError` small; the fields are reached through the box.
+    #[error("{0}")]
+    ApiError(Box<ApiError>),
     #[error("Failed to parse to String: {0}")]
     SseParseError(String),
     #[error("{0}")]
@@ -22,13 +40,314 @@ pub enum OapiError {
     /// If the request is a non-streaming request, but the context is streaming.
     #[error("You cannot post a non-streaming request in a streaming context")]
     StreamingViolation,
+    /// A request parameter was rejected locally, before sending the request.
+    #[error("Invalid parameter: {0}")]
+    InvalidParameter(String),
+    /// A request or stream exceeded one of the timeouts of the
+    /// [`Client`](crate::rest::Client).
+    #[error("Timed out: {0}")]
+    Timeout(String),
+    /// Every attempt allowed by the [`RetryPolicy`](crate::rest::retry::RetryPolicy)
+    /// of a [`Client`](crate::rest::Client) failed. `last` is the error of the
+    /// last attempt.
+    #[error("Gave up after {attempts} attempts: {last}")]
+    RetriesExhausted { attempts: u32, last: Box<OapiError> },
+    /// A conversation breaks a message ordering rule. `index` is the position of
+    /// the offending message, or the number of messages if the conversation
+    /// ended too early.
+    #[error("Invalid conversation at message {index}: {reason}")]
+    InvalidConversation { index: usize, reason: String },
+    /// A completion has no text to return: it has no choices, or its first
+    /// choice has neither content nor a refusal, e.g. only tool calls.
+    #[error("The response has no text content")]
+    EmptyResponse,
+    /// The model refused to answer. Holds the refusal message.
+    #[error("The model refused to answer: {0}")]
+    Refusal(String),
+    /// The runs of a request sent by
+    /// [`assert_reproducible`](crate::chat::determinism::assert_reproducible)
+    /// disagree. `diff` describes how, see
+    /// [`DeterminismCheck::diff`](crate::chat::determinism::DeterminismCheck::diff).
+    #[error("Request is not reproducible over {runs} runs:\n{diff}")]
+    NotReproducible { runs: usize, diff: String },
+    #[error("Serialization error: {0}")]
+    SerializationError(String),
     #[error("Deserialization error:\n{0}\n\nPlease report this error in the project issue.")]
     DeserializationError(String),
     #[error("File not found at: {0}")]
     FileNotFoundError(PathBuf),
     #[error("Failed to read file: {0}")]
     FileReadError(std::io::Error),
+    #[error("Failed to write file: {0}")]
+    FileWriteError(std::io::Error),
 
     #[error("Not implemented")]
     NotImplemented,
 }
+
+impl OapiError {
+    /// The HTTP status of the response, if the provider answered with an error
+    /// status.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::ResponseStatus(status) | Self::ResponseCode(status) => Some(*status),
+            Self::ApiError(error) => Some(error.status),
+            Self::RetriesExhausted { last, .. } => last.status(),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for OapiError {
+    /// Keeps the classification of reqwest: a timeout becomes
+    /// [`OapiError::Timeout`], a failed connection [`OapiError::Connect`], an
+    /// error status [`OapiError::ApiError`], a body that cannot be
+    /// decoded [`OapiError::Decode`], a failure to read the body
+    /// [`OapiError::ResponseError`], and any other failure
+    /// [`OapiError::SendError`].
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            Self::Timeout(error.to_string())
+        } else if error.is_connect() {
+            Self::Connect(format!("{:#?}", error))
+        } else if let Some(status) = error.status() {
+            // reqwest does not keep the body of an error status.
+            ApiError::from_body(
+                status.as_u16(),
+                status.canonical_reason().unwrap_or_default(),
+            )
+            .into()
+        } else if error.is_decode() {
+            Self::Decode(format!("{:#?}", error))
+        } else if error.is_body() {
+            Self::ResponseError(format!("Failed to read response body: {:#?}", error))
+        } else {
+            Self::SendError(format!("Failed to send request: {:#?}", error))
+        }
+    }
+}
+
+impl From<serde_json::Error> for OapiError {
+    /// Becomes [`OapiError::DeserializationError`], since responses are where
+    /// JSON fails to parse. Serializing a request reports
+    /// [`OapiError::SerializationError`] instead.
+    fn from(error: serde_json::Error) -> Self {
+        Self::DeserializationError(error.to_string())
+    }
+}
+
+impl From<ApiError> for OapiError {
+    fn from(error: ApiError) -> Self {
+        Self::ApiError(Box::new(error))
+    }
+}
+
+/// An error returned by the provider, parsed from the
+/// `{"error": {"message": ..., "type": ..., "param": ..., "code": ...}}` body
+/// of an error response.
+///
+/// Bodies in another format are kept whole in `message`, with the other fields
+/// empty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiError {
+    /// The HTTP status of the response.
+    pub status: u16,
+    /// A human-readable description of the error.
+    pub message: String,
+    /// The kind of error, e.g. `invalid_request_error`.
+    pub error_type: Option<String>,
+    /// The request parameter that caused the error, if any.
+    pub param: Option<String>,
+    /// A machine-readable error code, e.g. `insufficient_quota`. Numeric codes
+    /// are turned into strings.
+    pub code: Option<String>,
+    /// How long the provider asks to wait before sending the request again,
+    /// from the `Retry-After` header of the response. Only a number of seconds
+    /// is understood, not an HTTP date.
+    pub retry_after: Option<Duration>,
+    /// The ID the provider gave the request, from the `x-request-id` header of
+    /// the response. Providers ask for it in support requests.
+    pub request_id: Option<String>,
+    /// The Cloudflare ray ID of the response, from the `cf-ray` header, for
+    /// providers behind Cloudflare.
+    pub cf_ray: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ErrorEnvelope {
+    error: ErrorObject,
+}
+
+#[derive(Deserialize)]
+struct ErrorObject {
+    #[serde(default)]
+    message: String,
+    #[serde(rename = "type", default)]
+    error_type: Option<String>,
+    #[serde(default)]
+    param: Option<String>,
+    #[serde(default, deserialize_with = "crate::de::string_or_number")]
+    code: Option<String>,
+}
+
+impl ApiError {
+    /// Parses the body of an error response with the given status.
+    pub fn from_body(status: u16, body: &str) -> Self {
+        match serde_json::from_str::<ErrorEnvelope>(body) {
+            Ok(ErrorEnvelope { error }) => Self {
+                status,
+                message: error.message,
+                error_type: error.error_type,
+                param: error.param,
+                code: error.code,
+                retry_after: None,
+                request_id: None,
+                cf_ray: None,
+            },
+            Err(_) => Self {
+                status,
+                message: body.to_string(),
+                error_type: None,
+                param: None,
+                code: None,
+                retry_after: None,
+                request_id: None,
+                cf_ray: None,
+            },
+        }
+    }
+
+    /// Reads the body and the diagnostic headers of an error response.
+    pub(crate) async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status().as_u16();
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let request_id = header("x-request-id");
+        let cf_ray = header("cf-ray");
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<f64>().ok())
+            .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+            .map(Duration::from_secs_f64);
+        let body = response.text().await.unwrap_or_default();
+        Self {
+            retry_after,
+            request_id,
+            cf_ray,
+            ..Self::from_body(status, &body)
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "API error (status {})", self.status)?;
+        if let Some(code) = &self.code {
+            write!(f, " [{}]", code)?;
+        }
+        write!(f, ": {}", self.message)?;
+        if let Some(param) = &self.param {
+            write!(f, " (param: {})", param)?;
+        }
+        if let Some(request_id) = &self.request_id {
+            write!(f, " (request id: {})", request_id)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn convert_reqwest_and_serde_errors() {
+        let error: OapiError = serde_json::from_str::<u32>("\"1\"").unwrap_err().into();
+        assert!(matches!(error, OapiError::DeserializationError(_)));
+
+        // Nothing listens on port 1.
+        let error: OapiError = reqwest::get("http://127.0.0.1:1").await.unwrap_err().into();
+        assert!(matches!(error, OapiError::Connect(_)));
+        let error: OapiError = reqwest::get("not a url").await.unwrap_err().into();
+        assert!(matches!(error, OapiError::SendError(_)));
+
+        let server = crate::test_support::MockServer::start(vec![
+            crate::test_support::MockResponse::new(200, "not json"),
+            crate::test_support::MockResponse::new(404, ""),
+        ])
+        .await;
+        let response = reqwest::get(server.url("/")).await.unwrap();
+        let error: OapiError = response.json::<u32>().await.unwrap_err().into();
+        assert!(matches!(error, OapiError::Decode(_)));
+        let response = reqwest::get(server.url("/")).await.unwrap();
+        let error: OapiError = response.error_for_status().unwrap_err().into();
+        assert!(matches!(&error, OapiError::ApiError(error) if error.message == "Not Found"));
+        assert_eq!(error.status(), Some(404));
+    }
+
+    #[test]
+    fn parse_api_error() {
+        let error = ApiError::from_body(
+            429,
+            r#"{"error":{"message":"You exceeded your current quota.","type":"insufficient_quota","param":null,"code":"insufficient_quota"}}"#,
+        );
+        assert_eq!(
+            error,
+            ApiError {
+                status: 429,
+                message: "You exceeded your current quota.".to_string(),
+                error_type: Some("insufficient_quota".to_string()),
+                param: None,
+                code: Some("insufficient_quota".to_string()),
+                retry_after: None,
+                request_id: None,
+                cf_ray: None,
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "API error (status 429) [insufficient_quota]: You exceeded your current quota."
+        );
+
+        let numeric = ApiError::from_body(
+            400,
+            r#"{"error":{"message":"Invalid max_tokens","type":"invalid_request_error","param":"max_tokens","code":20015}}"#,
+        );
+        assert_eq!(numeric.code.as_deref(), Some("20015"));
+        assert_eq!(numeric.param.as_deref(), Some("max_tokens"));
+
+        let raw = ApiError::from_body(502, "<html>Bad Gateway</html>");
+        assert_eq!(raw.message, "<html>Bad Gateway</html>");
+        assert_eq!(raw.error_type, None);
+        assert_eq!(OapiError::ApiError(Box::new(raw)).status(), Some(502));
+    }
+
+    #[tokio::test]
+    async fn api_error_keeps_request_id() {
+        let server = crate::test_support::MockServer::start(vec![
+            crate::test_support::MockResponse::json(
+                500,
+                r#"{"error":{"message":"The server had an error.","type":"server_error"}}"#,
+            )
+            .with_header("x-request-id", "req_123")
+            .with_header("cf-ray", "8a1b2c3d4e5f-SJC"),
+        ])
+        .await;
+        let response = reqwest::get(server.url("/")).await.unwrap();
+        let error = ApiError::from_response(response).await;
+
+        assert_eq!(error.request_id.as_deref(), Some("req_123"));
+        assert_eq!(error.cf_ray.as_deref(), Some("8a1b2c3d4e5f-SJC"));
+        assert_eq!(
+            error.to_string(),
+            "API error (status 500): The server had an error. (request id: req_123)"
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! Downloading the contents of a file.
+//!
+//! The content endpoint, `/files/{file_id}/content`, returns the raw bytes of
+//! the file instead of JSON. `endpoint` in the methods below is the files
+//! endpoint, e.g. `https://api.openai.com/v1/files`. Batch outputs can be hundreds of
+//! megabytes, so [`RetrieveFileContentRequest::stream_to_writer`] writes the
+//! body as it arrives instead of holding it in memory.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use openai_interface::files::content::RetrieveFileContentRequest;
+//! use openai_interface::rest::Client;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let client = Client::new("https://api.openai.com/v1", "sk-...");
+//!     let request = RetrieveFileContentRequest {
+//!         file_id: "file-abc123".to_string(),
+//!     };
+//!
+//!     let mut file = tokio::fs::File::create("batch_output.jsonl").await?;
+//!     let bytes = request
+//!         .stream_to_writer(&client.url("files"), "sk-...", &mut file)
+//!         .await?;
+//!     println!("Downloaded {} bytes", bytes);
+//!     Ok(())
+//! }
+//! ```
+
+use tokio::io::AsyncWrite;
+
+use crate::errors::OapiError;
+use crate::rest::get::send_request;
+use crate::rest::post::write_body;
+
+/// Retrieves the contents of a file, at `/files/{file_id}/content`.
+#[derive(Debug, Clone, Default)]
+pub struct RetrieveFileContentRequest {
+    /// The ID of the file to download.
+    pub file_id: String,
+}
+
+impl RetrieveFileContentRequest {
+    /// The URL of the file contents, given the files endpoint.
+    pub fn url(&self, endpoint: &str) -> String {
+        format!(
+            "{}/{}/content",
+            endpoint.trim_end_matches('/'),
+            self.file_id
+        )
+    }
+
+    /// Downloads the whole file into memory.
+    pub async fn get_bytes(&self, endpoint: &str, key: &str) -> Result<Vec<u8>, OapiError> {
+        let response = send_request(reqwest::Method::GET, &self.url(endpoint), key, &[]).await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Streams the file to `writer` chunk by chunk, and returns the number of
+    /// bytes written. The writer is flushed at the end.
+    pub async fn stream_to_writer<W>(
+        &self,
+        endpoint: &str,
+        key: &str,
+        writer: &mut W,
+    ) -> Result<u64, OapiError>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let response = send_request(reqwest::Method::GET, &self.url(endpoint), key, &[]).await?;
+        write_body(response, writer).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockResponse, MockServer};
+
+    #[tokio::test]
+    async fn stream_binary_body() {
+        let body: Vec<u8> = (0..=255u8).cycle().take(100_000).collect();
+        let server = MockServer::start(vec![
+            MockResponse::new(200, body.clone())
+                .with_header("Content-Type", "application/octet-stream"),
+            MockResponse::json(404, "{}"),
+        ])
+        .await;
+        let request = RetrieveFileContentRequest {
+            file_id: "file-abc".to_string(),
+        };
+        let url = server.url("/v1/files");
+
+        let mut buffer = Vec::new();
+        let written = request
+            .stream_to_writer(&url, "key", &mut buffer)
+            .await
+            .unwrap();
+        assert_eq!(written, body.len() as u64);
+        assert_eq!(buffer, body);
+
+        let recorded = &server.requests()[0];
+        assert_eq!(recorded.method, "GET");
+        assert_eq!(recorded.path, "/v1/files/file-abc/content");
+        assert_eq!(recorded.header("authorization"), Some("Bearer key"));
+
+        assert!(matches!(
+            request.get_bytes(&url, "key").await,
+            Err(OapiError::ApiError(error)) if error.status == 404
+        ));
+    }
+}
@@ -124,14 +124,62 @@ mod tests {
     use std::{path::PathBuf, sync::LazyLock};
 
     use crate::rest::post::NoStream;
+    use crate::test_support::{MockResponse, MockServer};
 
     use super::*;
 
     const TEST_FILE_PATH: &str = "src/files/create/file-test.txt";
     const MODELSCOPE_URL: &str = "https://dashscope.aliyuncs.com/compatible-mode/v1/files";
-    const MODELSCOPE_KEY: LazyLock<&str> =
+    static MODELSCOPE_KEY: LazyLock<&str> =
         LazyLock::new(|| include_str!("../../../keys/modelstudio_domestic_key").trim());
 
+    #[tokio::test]
+    async fn upload_streams_file() {
+        let server = MockServer::start(vec![MockResponse::json(
+            200,
+            r#"{"id":"file-1","bytes":42,"created_at":1,"filename":"file-test.txt","object":"file","purpose":"batch"}"#,
+        )])
+        .await;
+        let request = request::CreateFileRequest {
+            file: PathBuf::from(TEST_FILE_PATH),
+            purpose: request::FilePurpose::Batch,
+            ..Default::default()
+        };
+
+        let file = request
+            .get_response(&server.url("/v1/files"), "key")
+            .await
+            .unwrap();
+        assert_eq!(file.id, "file-1");
+
+        // Sending through a client keeps the multipart body.
+        crate::rest::Client::new(server.url("/v1"), "key")
+            .post_no_stream("/files", &request)
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert!(
+            requests[1]
+                .header("content-type")
+                .is_some_and(|value| value.starts_with("multipart/form-data"))
+        );
+        let recorded = &requests[0];
+        let body = String::from_utf8_lossy(&recorded.body);
+        assert!(body.contains(r#"filename="file-test.txt""#));
+        assert!(body.contains(&std::fs::read_to_string(TEST_FILE_PATH).unwrap()));
+        assert!(body.contains("batch"));
+
+        let missing = request::CreateFileRequest {
+            file: PathBuf::from("src/files/create/missing.txt"),
+            ..Default::default()
+        };
+        assert!(matches!(
+            missing.get_response(&server.url("/v1/files"), "key").await,
+            Err(crate::errors::OapiError::FileNotFoundError(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_file_create() -> Result<(), anyhow::Error> {
         let file_path = PathBuf::from(TEST_FILE_PATH);
@@ -30,15 +30,30 @@ pub struct FileObject {
     pub status_details: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum FileStatus {
     Uploaded,
     Processed,
     Error,
+    /// A status this crate does not know yet.
+    #[serde(untagged)]
+    Other(String),
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl FileStatus {
+    /// Returns the status as sent by the provider.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Uploaded => "uploaded",
+            Self::Processed => "processed",
+            Self::Error => "error",
+            Self::Other(status) => status,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
 pub enum FilePurpose {
     #[serde(rename = "assistant")]
     Assistant,
@@ -60,12 +75,44 @@ pub enum FilePurpose {
     Other(String),
 }
 
+impl FilePurpose {
+    /// Returns the purpose as sent by the provider.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Assistant => "assistant",
+            Self::AssistantsOutput => "assistants_output",
+            Self::Batch => "batch",
+            Self::BatchOutput => "batch_output",
+            Self::FineTune => "fine-tune",
+            Self::FineTuneResults => "fine-tune-results",
+            Self::Vision => "vision",
+            Self::UserData => "user_data",
+            Self::Other(purpose) => purpose,
+        }
+    }
+}
+
 impl FromStr for FileObject {
     type Err = OapiError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parse_result: Result<Self, _> =
-            serde_json::from_str(s).map_err(|e| OapiError::DeserializationError(e.to_string()));
-        parse_result
+        Ok(serde_json::from_str(s)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_status_and_purpose() {
+        let file = FileObject::from_str(
+            r#"{"id":"file-abc","bytes":120,"created_at":1677610602,"filename":"mydata.jsonl","object":"file","purpose":"evals","status":"scanning"}"#,
+        )
+        .unwrap();
+        assert_eq!(file.purpose, FilePurpose::Other("evals".to_string()));
+        assert_eq!(file.purpose.as_str(), "evals");
+        assert_eq!(file.status, Some(FileStatus::Other("scanning".to_string())));
+        assert_eq!(file.status.unwrap().as_str(), "scanning");
     }
 }
@@ -0,0 +1,41 @@
+//! Deleting a file.
+
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::errors::OapiError;
+use crate::rest::delete::Delete;
+
+/// Deletes a file, at `/files/{file_id}`.
+#[derive(Debug, Clone, Default)]
+pub struct DeleteFileRequest {
+    /// The ID of the file to delete.
+    pub file_id: String,
+}
+
+impl Delete for DeleteFileRequest {
+    type Response = DeletionStatus;
+
+    fn url(&self, endpoint: &str) -> String {
+        format!("{}/{}", endpoint.trim_end_matches('/'), self.file_id)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct DeletionStatus {
+    /// The ID of the deleted file.
+    pub id: String,
+    /// The object type, which is always `file`.
+    pub object: String,
+    /// Whether the file was deleted.
+    pub deleted: bool,
+}
+
+impl FromStr for DeletionStatus {
+    type Err = OapiError;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(content).map_err(OapiError::from)
+    }
+}
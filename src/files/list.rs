@@ -0,0 +1,74 @@
+//! Listing the uploaded files.
+
+use futures_util::Stream;
+
+use crate::errors::OapiError;
+use crate::rest::get::Get;
+use crate::rest::pagination::{Page, PageItem, paginate};
+
+use super::create::response::FileObject;
+
+/// Lists one page of files, at `/files`.
+///
+/// [`ListFilesRequest::stream_all`] and [`files::list`](fn@super::list) fetch
+/// every page instead.
+#[derive(Debug, Clone, Default)]
+pub struct ListFilesRequest {
+    /// Only return files with this purpose, such as `batch` or `fine-tune`.
+    pub purpose: Option<String>,
+    /// The number of files to return, between 1 and 10,000.
+    pub limit: Option<usize>,
+    /// Sort order by the `created_at` timestamp: `asc` or `desc`.
+    pub order: Option<String>,
+    /// The ID of the file after which the page starts, for pagination.
+    pub after: Option<String>,
+}
+
+impl ListFilesRequest {
+    /// Streams every file matching this request, fetching the next page of
+    /// the files endpoint at `url` only when the items of the previous one
+    /// have been consumed.
+    ///
+    /// The stream starts after `after` if it is set, and ends after the first
+    /// error.
+    pub fn stream_all<'a>(
+        &'a self,
+        url: &'a str,
+        key: &'a str,
+    ) -> impl Stream<Item = Result<FileObject, OapiError>> + 'a {
+        paginate(move |after| {
+            let request = ListFilesRequest {
+                after: after.or_else(|| self.after.clone()),
+                ..self.clone()
+            };
+            async move { request.get_response(url, key).await }
+        })
+    }
+}
+
+impl Get for ListFilesRequest {
+    type Response = Page<FileObject>;
+
+    fn query(&self) -> Vec<(&'static str, String)> {
+        let mut query = Vec::new();
+        if let Some(purpose) = &self.purpose {
+            query.push(("purpose", purpose.clone()));
+        }
+        if let Some(limit) = self.limit {
+            query.push(("limit", limit.to_string()));
+        }
+        if let Some(order) = &self.order {
+            query.push(("order", order.clone()));
+        }
+        if let Some(after) = &self.after {
+            query.push(("after", after.clone()));
+        }
+        query
+    }
+}
+
+impl PageItem for FileObject {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
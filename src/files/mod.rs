@@ -1 +1,162 @@
+//! Uploading and managing files.
+//!
+//! `url` in the functions below is the files endpoint, e.g.
+//! `https://api.openai.com/v1/files`.
+
+pub mod content;
 pub mod create;
+pub mod delete;
+pub mod list;
+pub mod retrieve;
+
+use futures_util::TryStreamExt;
+
+use crate::errors::OapiError;
+use crate::rest::delete::Delete;
+use crate::rest::get::Get;
+use content::RetrieveFileContentRequest;
+use create::response::FileObject;
+use delete::{DeleteFileRequest, DeletionStatus};
+use list::ListFilesRequest;
+use retrieve::RetrieveFileRequest;
+
+/// Retrieves the file `file_id`, at `{url}/{file_id}`.
+pub async fn retrieve(url: &str, key: &str, file_id: &str) -> Result<FileObject, OapiError> {
+    RetrieveFileRequest {
+        file_id: file_id.to_string(),
+    }
+    .get_response(url, key)
+    .await
+}
+
+/// Lists every uploaded file, following the pages of the list endpoint.
+///
+/// All pages are held in memory. To filter by purpose, or to handle the files
+/// as the pages arrive, use [`ListFilesRequest::stream_all`] instead.
+pub async fn list(url: &str, key: &str) -> Result<Vec<FileObject>, OapiError> {
+    ListFilesRequest::default()
+        .stream_all(url, key)
+        .try_collect()
+        .await
+}
+
+/// Downloads the contents of the file `file_id`, at `{url}/{file_id}/content`,
+/// e.g. the `output_file_id` of a completed batch.
+///
+/// The whole file is held in memory. To write a large file as it arrives, use
+/// [`RetrieveFileContentRequest::stream_to_writer`] instead.
+pub async fn content(url: &str, key: &str, file_id: &str) -> Result<Vec<u8>, OapiError> {
+    RetrieveFileContentRequest {
+        file_id: file_id.to_string(),
+    }
+    .get_bytes(url, key)
+    .await
+}
+
+/// Deletes the file `file_id`, at `{url}/{file_id}`.
+pub async fn delete(url: &str, key: &str, file_id: &str) -> Result<DeletionStatus, OapiError> {
+    DeleteFileRequest {
+        file_id: file_id.to_string(),
+    }
+    .get_response(url, key)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+
+    use super::*;
+    use crate::test_support::{MockResponse, MockServer};
+
+    const FILE: &str = r#"{"id":"file-1","bytes":120,"created_at":1677610602,"filename":"batch.jsonl","object":"file","purpose":"batch"}"#;
+
+    #[tokio::test]
+    async fn retrieve_list_download_and_delete() {
+        let server = MockServer::start(vec![
+            MockResponse::json(200, FILE),
+            MockResponse::json(
+                200,
+                &format!(r#"{{"object":"list","data":[{}],"has_more":true}}"#, FILE),
+            ),
+            MockResponse::json(200, r#"{"object":"list","data":[],"has_more":false}"#),
+            MockResponse::json(
+                200,
+                &format!(r#"{{"object":"list","data":[{}],"has_more":false}}"#, FILE),
+            ),
+            MockResponse::new(200, "{\"custom_id\":\"request-1\"}\n"),
+            MockResponse::json(200, r#"{"id":"file-1","object":"file","deleted":true}"#),
+        ])
+        .await;
+        let url = server.url("/v1/files");
+
+        assert_eq!(retrieve(&url, "key", "file-1").await.unwrap().id, "file-1");
+        assert_eq!(list(&url, "key").await.unwrap().len(), 1);
+        let page = ListFilesRequest {
+            purpose: Some("batch".to_string()),
+            limit: Some(10),
+            ..Default::default()
+        }
+        .get_response(&url, "key")
+        .await
+        .unwrap();
+        assert_eq!(page.data[0].filename, "batch.jsonl");
+        assert_eq!(
+            content(&url, "key", "file-1").await.unwrap(),
+            b"{\"custom_id\":\"request-1\"}\n"
+        );
+        assert!(delete(&url, "key", "file-1").await.unwrap().deleted);
+
+        let requests = server.requests();
+        let calls: Vec<(&str, &str)> = requests
+            .iter()
+            .map(|r| (r.method.as_str(), r.path.as_str()))
+            .collect();
+        assert_eq!(
+            calls,
+            vec![
+                ("GET", "/v1/files/file-1"),
+                ("GET", "/v1/files"),
+                ("GET", "/v1/files?after=file-1"),
+                ("GET", "/v1/files?purpose=batch&limit=10"),
+                ("GET", "/v1/files/file-1/content"),
+                ("DELETE", "/v1/files/file-1"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_all_fetches_pages_lazily() {
+        let server = MockServer::start(vec![
+            MockResponse::json(
+                200,
+                &format!(r#"{{"object":"list","data":[{}],"has_more":true}}"#, FILE),
+            ),
+            MockResponse::json(
+                200,
+                &format!(r#"{{"object":"list","data":[{}],"has_more":false}}"#, FILE),
+            ),
+        ])
+        .await;
+        let url = server.url("/v1/files");
+        let request = ListFilesRequest {
+            purpose: Some("batch".to_string()),
+            ..Default::default()
+        };
+
+        let mut files = Box::pin(request.stream_all(&url, "key"));
+        assert_eq!(files.next().await.unwrap().unwrap().id, "file-1");
+        assert_eq!(server.requests().len(), 1);
+        assert!(files.next().await.unwrap().is_ok());
+        assert!(files.next().await.is_none());
+
+        let paths: Vec<String> = server.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/v1/files?purpose=batch",
+                "/v1/files?purpose=batch&after=file-1"
+            ]
+        );
+    }
+}
@@ -0,0 +1,20 @@
+//! Retrieving the information about one file.
+
+use crate::rest::get::Get;
+
+use super::create::response::FileObject;
+
+/// Retrieves a file object, at `/files/{file_id}`.
+#[derive(Debug, Clone, Default)]
+pub struct RetrieveFileRequest {
+    /// The ID of the file to retrieve.
+    pub file_id: String,
+}
+
+impl Get for RetrieveFileRequest {
+    type Response = FileObject;
+
+    fn url(&self, endpoint: &str) -> String {
+        format!("{}/{}", endpoint.trim_end_matches('/'), self.file_id)
+    }
+}
@@ -13,6 +13,9 @@
 //! - **Async/Await**: Built with async/await support for efficient asynchronous operations
 //! - **Musl Support**: Designed to work with musl libc for lightweight deployments
 //!
+//! The request, response and error types of chat completions, and the traits
+//! sending them, can be imported at once with `use openai_interface::prelude::*;`.
+//!
 //! # Examples
 //!
 //! ## Non-streaming Chat Completion
@@ -28,8 +31,8 @@
 //! // You need to provide your own DeepSeek API key at /keys/deepseek_domestic_key
 //! const DEEPSEEK_API_KEY: LazyLock<&str> =
 //!    LazyLock::new(|| include_str!("../keys/deepseek_domestic_key").trim());
-//! const DEEPSEEK_CHAT_URL: &'static str = "https://api.deepseek.com/chat/completions";
-//! const DEEPSEEK_MODEL: &'static str = "deepseek-chat";
+//! const DEEPSEEK_CHAT_URL: &str = "https://api.deepseek.com/chat/completions";
+//! const DEEPSEEK_MODEL: &str = "deepseek-chat";
 //!
 //! #[tokio::main]
 //! async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -40,7 +43,7 @@
 //!                 name: None,
 //!             },
 //!             Message::User {
-//!                 content: "Hello, how are you?".to_string(),
+//!                 content: "Hello, how are you?".into(),
 //!                 name: None,
 //!             },
 //!         ],
@@ -53,11 +56,7 @@
 //!     let chat_completion: ChatCompletion = request
 //!         .get_response(DEEPSEEK_CHAT_URL, &*DEEPSEEK_API_KEY)
 //!         .await?;
-//!     let text = chat_completion.choices[0]
-//!         .message
-//!         .content
-//!         .as_deref()
-//!         .unwrap();
+//!     let text = chat_completion.text()?;
 //!     println!("{:?}", text);
 //!     Ok(())
 //! }
@@ -79,8 +78,8 @@
 //! // You need to provide your own DeepSeek API key at /keys/deepseek_domestic_key
 //! const DEEPSEEK_API_KEY: LazyLock<&str> =
 //!    LazyLock::new(|| include_str!("../keys/deepseek_domestic_key").trim());
-//! const DEEPSEEK_CHAT_URL: &'static str = "https://api.deepseek.com/chat/completions";
-//! const DEEPSEEK_MODEL: &'static str = "deepseek-chat";
+//! const DEEPSEEK_CHAT_URL: &str = "https://api.deepseek.com/chat/completions";
+//! const DEEPSEEK_MODEL: &str = "deepseek-chat";
 //!
 //! #[tokio::main]
 //! async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -91,7 +90,7 @@
 //!                 name: None,
 //!             },
 //!             Message::User {
-//!                 content: "Who are you?".to_string(),
+//!                 content: "Who are you?".into(),
 //!                 name: None,
 //!             },
 //!         ],
@@ -109,7 +108,15 @@
 //!
 //!     while let Some(chunk_result) = response_stream.next().await {
 //!         let chunk: ChatCompletionChunk = chunk_result?;
-//!         let content = match chunk.choices[0].delta.content.as_ref().unwrap() {
+//!         // The last chunk may only carry usage, without choices.
+//!         let Some(content) = chunk
+//!             .choices
+//!             .first()
+//!             .and_then(|choice| choice.delta.content.as_ref())
+//!         else {
+//!             continue;
+//!         };
+//!         let content = match content {
 //!             CompletionContent::Content(s) => s,
 //!             CompletionContent::ReasoningContent(s) => s,
 //!         };
@@ -133,12 +140,29 @@
 //! rustup target add x86_64-unknown-linux-musl
 //! cargo build --target x86_64-unknown-linux-musl
 //! ```
+//!
+//! To skip OpenSSL, use rustls instead of the default `native-tls` feature:
+//! ```bash
+//! cargo build --target x86_64-unknown-linux-musl --no-default-features --features rustls
+//! ```
 
+pub mod audio;
+pub mod batch;
 pub mod chat;
 pub mod completions;
+mod de;
+pub mod embeddings;
 pub mod errors;
 pub mod files;
+pub mod models;
+pub mod moderations;
+pub mod prelude;
+pub mod responses;
 pub mod rest;
+pub mod uploads;
+
+#[cfg(test)]
+mod test_support;
 
 #[cfg(test)]
 mod tests {
@@ -150,10 +174,10 @@ mod tests {
     use std::sync::LazyLock;
 
     // You need to provide your own DeepSeek API key at /keys/deepseek_domestic_key
-    const DEEPSEEK_API_KEY: LazyLock<&str> =
+    static DEEPSEEK_API_KEY: LazyLock<&str> =
         LazyLock::new(|| include_str!("../keys/deepseek_domestic_key").trim());
-    const DEEPSEEK_CHAT_URL: &'static str = "https://api.deepseek.com/chat/completions";
-    const DEEPSEEK_MODEL: &'static str = "deepseek-chat";
+    const DEEPSEEK_CHAT_URL: &str = "https://api.deepseek.com/chat/completions";
+    const DEEPSEEK_MODEL: &str = "deepseek-chat";
 
     #[tokio::test]
     async fn test_no_streaming() -> Result<(), Box<dyn std::error::Error>> {
@@ -164,7 +188,7 @@ mod tests {
                     name: None,
                 },
                 Message::User {
-                    content: "Hello, how are you?".to_string(),
+                    content: "Hello, how are you?".into(),
                     name: None,
                 },
             ],
@@ -175,7 +199,7 @@ mod tests {
 
         // Send the request
         let chat_completion: ChatCompletion = request
-            .get_response(DEEPSEEK_CHAT_URL, &*DEEPSEEK_API_KEY)
+            .get_response(DEEPSEEK_CHAT_URL, *DEEPSEEK_API_KEY)
             .await?;
         let text = chat_completion.choices[0]
             .message
@@ -195,7 +219,7 @@ mod tests {
                     name: None,
                 },
                 Message::User {
-                    content: "Who are you?".to_string(),
+                    content: "Who are you?".into(),
                     name: None,
                 },
             ],
@@ -213,7 +237,15 @@ mod tests {
 
         while let Some(chunk_result) = response_stream.next().await {
             let chunk: ChatCompletionChunk = chunk_result?;
-            let content = match chunk.choices[0].delta.content.as_ref().unwrap() {
+            // The last chunk may only carry usage, without choices.
+            let Some(content) = chunk
+                .choices
+                .first()
+                .and_then(|choice| choice.delta.content.as_ref())
+            else {
+                continue;
+            };
+            let content = match content {
                 CompletionContent::Content(s) => s,
                 CompletionContent::ReasoningContent(s) => s,
             };
@@ -0,0 +1,93 @@
+//! Lists and describes the models a provider offers.
+//!
+//! The model list changes as providers add and retire models, so a UI can
+//! fetch it instead of hard-coding model IDs.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use openai_interface::models::{list_models, retrieve_model};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let url = "https://api.deepseek.com/models";
+//!     for model in list_models(url, "sk-...").await? {
+//!         println!("{} (owned by {})", model.id, model.owned_by);
+//!     }
+//!
+//!     let model = retrieve_model(url, "sk-...", "deepseek-chat").await?;
+//!     println!("{:?}", model.created);
+//!     Ok(())
+//! }
+//! ```
+
+pub mod request;
+pub mod response;
+
+use crate::errors::OapiError;
+use crate::rest::get::Get;
+use request::{ListModelsRequest, RetrieveModelRequest};
+use response::Model;
+
+/// Lists the models available at the models endpoint `url`, e.g.
+/// `https://api.openai.com/v1/models`.
+pub async fn list_models(url: &str, key: &str) -> Result<Vec<Model>, OapiError> {
+    let list = ListModelsRequest.get_response(url, key).await?;
+    Ok(list.data)
+}
+
+/// Retrieves the model `id` from the models endpoint `url`, at `{url}/{id}`.
+pub async fn retrieve_model(url: &str, key: &str, id: &str) -> Result<Model, OapiError> {
+    RetrieveModelRequest {
+        model: id.to_string(),
+    }
+    .get_response(url, key)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockResponse, MockServer};
+
+    #[tokio::test]
+    async fn list_and_retrieve() {
+        let server = MockServer::start(vec![
+            MockResponse::json(
+                200,
+                r#"{"object":"list","data":[
+                    {"id":"gpt-4o-mini","object":"model","created":1721172741,"owned_by":"system"},
+                    {"id":"deepseek-chat","object":"model","owned_by":"deepseek"}
+                ]}"#,
+            ),
+            MockResponse::json(
+                200,
+                r#"{"id":"gpt-4o-mini","object":"model","created":1721172741,"owned_by":"system"}"#,
+            ),
+            MockResponse::json(
+                404,
+                r#"{"error":{"message":"The model `gpt-5` does not exist"}}"#,
+            ),
+        ])
+        .await;
+        let url = server.url("/v1/models/");
+
+        let models = list_models(&url, "key").await.unwrap();
+        let ids: Vec<&str> = models.iter().map(|model| model.id.as_str()).collect();
+        assert_eq!(ids, vec!["gpt-4o-mini", "deepseek-chat"]);
+        assert_eq!(models[1].created, None);
+
+        let model = retrieve_model(&url, "key", "gpt-4o-mini").await.unwrap();
+        assert_eq!(model.created, Some(1721172741));
+        assert!(matches!(
+            retrieve_model(&url, "key", "gpt-5").await,
+            Err(OapiError::ApiError(error)) if error.status == 404
+        ));
+
+        let recorded = server.requests();
+        assert_eq!(recorded[0].method, "GET");
+        assert_eq!(recorded[0].path, "/v1/models/");
+        assert_eq!(recorded[1].path, "/v1/models/gpt-4o-mini");
+        assert_eq!(recorded[1].header("authorization"), Some("Bearer key"));
+    }
+}
@@ -0,0 +1,24 @@
+use crate::rest::get::Get;
+
+/// Lists the models available, at `/models`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ListModelsRequest;
+
+impl Get for ListModelsRequest {
+    type Response = super::response::ModelList;
+}
+
+/// Retrieves one model, at `/models/{model}`.
+#[derive(Debug, Clone, Default)]
+pub struct RetrieveModelRequest {
+    /// The ID of the model to retrieve.
+    pub model: String,
+}
+
+impl Get for RetrieveModelRequest {
+    type Response = super::response::Model;
+
+    fn url(&self, endpoint: &str) -> String {
+        format!("{}/{}", endpoint.trim_end_matches('/'), self.model)
+    }
+}
@@ -0,0 +1,43 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::errors::OapiError;
+
+/// The response of [`ListModelsRequest`](super::request::ListModelsRequest).
+#[derive(Debug, Deserialize, Clone)]
+pub struct ModelList {
+    /// The object type, which is always "list".
+    pub object: String,
+    pub data: Vec<Model>,
+}
+
+/// A model offered by the provider.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Model {
+    /// The model identifier, which can be referenced in the API endpoints.
+    pub id: String,
+    /// The Unix timestamp (in seconds) when the model was created. Some
+    /// providers, such as DeepSeek, leave it out.
+    pub created: Option<usize>,
+    /// The organization that owns the model.
+    pub owned_by: String,
+    /// The object type, which is always "model".
+    pub object: String,
+}
+
+impl FromStr for ModelList {
+    type Err = OapiError;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(content).map_err(OapiError::from)
+    }
+}
+
+impl FromStr for Model {
+    type Err = OapiError;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(content).map_err(OapiError::from)
+    }
+}
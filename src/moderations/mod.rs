@@ -0,0 +1,32 @@
+//! Classifies whether text is potentially harmful.
+//!
+//! The moderation endpoint flags input across categories such as harassment,
+//! hate and violence, with a score for each, so content can be filtered before
+//! it reaches a model or a user.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use openai_interface::moderations::request::ModerationRequest;
+//! use openai_interface::rest::post::NoStream;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let request = ModerationRequest {
+//!         model: Some("omni-moderation-latest".to_string()),
+//!         input: "I want to hurt them.".into(),
+//!         ..Default::default()
+//!     };
+//!
+//!     let response = request
+//!         .get_response("https://api.openai.com/v1/moderations", "sk-...")
+//!         .await?;
+//!     for result in &response.results {
+//!         println!("flagged: {}", result.flagged);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+pub mod request;
+pub mod response;
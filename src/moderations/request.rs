@@ -0,0 +1,63 @@
+use serde::Serialize;
+
+use crate::rest::post::{NoStream, Post};
+
+#[derive(Debug, Serialize, Default, Clone)]
+pub struct ModerationRequest {
+    /// The moderation model to use, e.g. `omni-moderation-latest`. Defaults to
+    /// the provider's latest model when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Input to classify, as a string or an array of strings.
+    pub input: ModerationInput,
+    /// Add additional JSON properties to the request
+    #[serde(flatten)]
+    pub extra_body: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(untagged)]
+pub enum ModerationInput {
+    /// String
+    String(String),
+    /// Array of strings, classified in one request
+    Strings(Vec<String>),
+}
+
+impl Default for ModerationInput {
+    fn default() -> Self {
+        Self::String("".to_string())
+    }
+}
+
+impl From<String> for ModerationInput {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&str> for ModerationInput {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<Vec<String>> for ModerationInput {
+    fn from(value: Vec<String>) -> Self {
+        Self::Strings(value)
+    }
+}
+
+impl Post for ModerationRequest {
+    fn is_streaming(&self) -> bool {
+        false
+    }
+
+    fn path(&self) -> Option<String> {
+        Some("/moderations".to_string())
+    }
+}
+
+impl NoStream for ModerationRequest {
+    type Response = super::response::ModerationResponse;
+}
@@ -0,0 +1,68 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use serde::Deserialize;
+
+use crate::errors::OapiError;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ModerationResponse {
+    /// The unique identifier for the moderation request.
+    pub id: String,
+    /// The model used to generate the moderation results.
+    pub model: String,
+    /// A list of moderation objects, one per input.
+    pub results: Vec<ModerationResult>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ModerationResult {
+    /// Whether any of the categories are flagged.
+    pub flagged: bool,
+    /// Whether each category is flagged, by category name such as `hate` or
+    /// `violence/graphic`.
+    pub categories: BTreeMap<String, bool>,
+    /// The score of each category, between 0 and 1, by category name.
+    pub category_scores: BTreeMap<String, f32>,
+}
+
+impl ModerationResult {
+    /// The names of the flagged categories.
+    pub fn flagged_categories(&self) -> impl Iterator<Item = &str> {
+        self.categories
+            .iter()
+            .filter(|(_, flagged)| **flagged)
+            .map(|(category, _)| category.as_str())
+    }
+}
+
+impl FromStr for ModerationResponse {
+    type Err = OapiError;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(content).map_err(OapiError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_moderation() {
+        let response = ModerationResponse::from_str(
+            r#"{"id":"modr-0d9740456c391e43","model":"omni-moderation-latest","results":[{"flagged":true,
+            "categories":{"harassment":true,"hate":false,"violence":true,"violence/graphic":false},
+            "category_scores":{"harassment":0.81,"hate":0.02,"violence":0.93,"violence/graphic":0.01},
+            "category_applied_input_types":{"harassment":["text"]}}]}"#,
+        )
+        .unwrap();
+
+        let result = &response.results[0];
+        assert!(result.flagged);
+        assert_eq!(result.category_scores["violence"], 0.93);
+        assert_eq!(
+            result.flagged_categories().collect::<Vec<_>>(),
+            vec!["harassment", "violence"]
+        );
+    }
+}
@@ -0,0 +1,19 @@
+//! The types and traits needed by most chat requests, for a glob import.
+//!
+//! ```rust
+//! use openai_interface::prelude::*;
+//!
+//! let request = RequestBody {
+//!     messages: vec![Message::user("Hello!")],
+//!     model: "deepseek-chat".to_string(),
+//!     ..Default::default()
+//! };
+//! assert!(!request.is_streaming());
+//! ```
+
+pub use crate::chat::request::{Message, RequestBody};
+pub use crate::chat::response::no_streaming::ChatCompletion;
+pub use crate::chat::response::streaming::{ChatCompletionChunk, CompletionContent};
+pub use crate::errors::OapiError;
+pub use crate::rest::Client;
+pub use crate::rest::post::{NoStream, Post, Stream};
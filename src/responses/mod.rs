@@ -0,0 +1,8 @@
+//! The Responses API, OpenAI's newer interface for generating model responses.
+//!
+//! Compared to the `chat` API, one response can carry several kinds of output
+//! items, such as text messages, reasoning summaries, function calls and generated
+//! images. See [`response::OutputItem`] for the supported items.
+
+pub mod request;
+pub mod response;
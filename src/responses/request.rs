@@ -0,0 +1,138 @@
+use serde::Serialize;
+
+use super::response::Response;
+use crate::rest::post::{NoStream, Post};
+
+/// A request to the Responses API, usually at `/responses`.
+#[derive(Debug, Serialize, Default, Clone)]
+pub struct ResponsesRequest {
+    /// Model ID used to generate the response, like `gpt-4o` or `o3`.
+    pub model: String,
+    /// Text or message inputs to the model, used to generate a response.
+    pub input: ResponseInput,
+    /// A system (or developer) message inserted into the model's context.
+    ///
+    /// When used along with `previous_response_id`, the instructions from a
+    /// previous response will not be carried over to the next response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    /// An upper bound for the number of tokens that can be generated for a
+    /// response, including visible output tokens and reasoning tokens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+    /// The unique ID of the previous response to the model. Use this to create
+    /// multi-turn conversations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_response_id: Option<String>,
+    /// Whether to store the generated model response for later retrieval via API.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store: Option<bool>,
+    /// Whether the response is streamed as server-sent events. Streaming is not
+    /// supported by this crate for the Responses API yet, so keep it `false`.
+    pub stream: bool,
+    /// What sampling temperature to use, between 0 and 2.
+    ///
+    /// It is generally recommended to alter this or `top_p` but not both.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// An alternative to sampling with temperature, called nucleus sampling.
+    ///
+    /// It is generally recommended to alter this or `temperature` but not both.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// The truncation strategy to use for the model response.
+    ///
+    /// With [`Truncation::Auto`], if the input of this response and the previous
+    /// ones exceeds the model's context window, the model truncates the response
+    /// to fit by dropping input items in the middle of the conversation. With
+    /// [`Truncation::Disabled`], the provider's default, a request exceeding the
+    /// context window fails with a 400 error instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncation: Option<Truncation>,
+    /// A stable identifier for your end-users, used to help detect abuse.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// Add additional JSON properties to the request
+    #[serde(flatten)]
+    pub extra_body: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(untagged)]
+pub enum ResponseInput {
+    /// A text input, equivalent to a single user message.
+    Text(String),
+    /// A list of messages with different roles.
+    Messages(Vec<InputMessage>),
+}
+
+impl Default for ResponseInput {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
+/// A message input to the model.
+#[derive(Debug, Serialize, Clone)]
+pub struct InputMessage {
+    pub role: InputRole,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InputRole {
+    User,
+    Assistant,
+    System,
+    Developer,
+}
+
+/// How the provider handles an input exceeding the model's context window.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Truncation {
+    /// Drop items from the middle of the conversation to fit the context window.
+    Auto,
+    /// Fail the request if it exceeds the context window.
+    Disabled,
+}
+
+impl Post for ResponsesRequest {
+    fn is_streaming(&self) -> bool {
+        self.stream
+    }
+
+    fn path(&self) -> Option<String> {
+        Some("/responses".to_string())
+    }
+}
+
+impl NoStream for ResponsesRequest {
+    type Response = Response;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_truncation() {
+        let mut request = ResponsesRequest {
+            model: "gpt-4o".to_string(),
+            input: ResponseInput::Text("Summarize this book.".to_string()),
+            ..Default::default()
+        };
+        let value = serde_json::to_value(&request).unwrap();
+        assert!(value.get("truncation").is_none());
+
+        request.truncation = Some(Truncation::Auto);
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(value["truncation"], "auto");
+        assert_eq!(value["input"], "Summarize this book.");
+
+        request.truncation = Some(Truncation::Disabled);
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(value["truncation"], "disabled");
+    }
+}
@@ -0,0 +1,303 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::errors::OapiError;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Response {
+    /// Unique identifier for this Response.
+    pub id: String,
+    /// The object type of this resource, which is always `response`.
+    pub object: String,
+    /// Unix timestamp (in seconds) of when this Response was created.
+    pub created_at: u64,
+    /// Model ID used to generate the response.
+    pub model: String,
+    /// The status of the response generation.
+    pub status: Option<ResponseStatus>,
+    /// An array of content items generated by the model.
+    ///
+    /// The length and order of items in the `output` array depend on the model's
+    /// response. Use the accessors such as [`Response::output_text`] rather than
+    /// assuming the first item is the assistant message.
+    pub output: Vec<OutputItem>,
+    /// Token usage details, including input tokens, output tokens and a breakdown
+    /// of output tokens.
+    pub usage: Option<ResponseUsage>,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseStatus {
+    Completed,
+    Failed,
+    InProgress,
+    Cancelled,
+    Queued,
+    Incomplete,
+    #[serde(untagged)]
+    Other(String),
+}
+
+/// An output item generated by the model.
+///
+/// Item types that are not modeled here are kept as raw JSON in
+/// [`OutputItem::Other`], so new item types do not break parsing of the whole
+/// response.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutputItem {
+    /// An output message from the model.
+    Message(OutputMessage),
+    /// A description of the chain of thought used by a reasoning model.
+    Reasoning(ReasoningItem),
+    /// A tool call to run a function.
+    FunctionCall(FunctionToolCall),
+    /// An image generation request made by the model.
+    ImageGenerationCall(ImageGenerationCall),
+    /// Any other output item, kept as raw JSON.
+    #[serde(untagged)]
+    Other(serde_json::Value),
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct OutputMessage {
+    /// The unique ID of the output message.
+    pub id: String,
+    /// The role of the output message. Always `assistant`.
+    pub role: String,
+    /// The status of the message.
+    pub status: Option<String>,
+    /// The content of the output message.
+    pub content: Vec<OutputContent>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutputContent {
+    /// A text output from the model.
+    OutputText {
+        /// The text output from the model.
+        text: String,
+        /// The annotations of the text output.
+        #[serde(default)]
+        annotations: Vec<serde_json::Value>,
+    },
+    /// An audio output from the model.
+    OutputAudio {
+        /// Base64-encoded audio data from the model.
+        data: String,
+        /// The transcript of the audio data from the model.
+        transcript: Option<String>,
+    },
+    /// A refusal from the model.
+    Refusal {
+        /// The refusal explanation from the model.
+        refusal: String,
+    },
+    /// Any other content part, kept as raw JSON.
+    #[serde(untagged)]
+    Other(serde_json::Value),
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ReasoningItem {
+    /// The unique identifier of the reasoning content.
+    pub id: String,
+    /// Reasoning summary content.
+    #[serde(default)]
+    pub summary: Vec<ReasoningSummary>,
+    /// The encrypted content of the reasoning item.
+    pub encrypted_content: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ReasoningSummary {
+    /// A summary of the reasoning output from the model so far.
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct FunctionToolCall {
+    /// The unique ID of the function tool call.
+    pub id: Option<String>,
+    /// The unique ID of the function tool call generated by the model.
+    pub call_id: String,
+    /// The name of the function to run.
+    pub name: String,
+    /// A JSON string of the arguments to pass to the function.
+    pub arguments: String,
+    /// The status of the item.
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ImageGenerationCall {
+    /// The unique ID of the image generation call.
+    pub id: String,
+    /// The status of the image generation call.
+    pub status: Option<String>,
+    /// The generated image encoded in base64.
+    pub result: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ResponseUsage {
+    /// The number of input tokens.
+    pub input_tokens: usize,
+    /// The number of output tokens.
+    pub output_tokens: usize,
+    /// The total number of tokens used.
+    pub total_tokens: usize,
+    /// A detailed breakdown of the input tokens.
+    pub input_tokens_details: Option<InputTokensDetails>,
+    /// A detailed breakdown of the output tokens.
+    pub output_tokens_details: Option<OutputTokensDetails>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct InputTokensDetails {
+    /// The number of tokens that were retrieved from the cache.
+    pub cached_tokens: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct OutputTokensDetails {
+    /// The number of reasoning tokens.
+    pub reasoning_tokens: Option<usize>,
+}
+
+impl Response {
+    /// Concatenates the text of all `output_text` parts in the output messages.
+    pub fn output_text(&self) -> String {
+        self.output
+            .iter()
+            .filter_map(|item| match item {
+                OutputItem::Message(message) => Some(&message.content),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|content| match content {
+                OutputContent::OutputText { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns all function calls made by the model, in output order.
+    pub fn function_calls(&self) -> Vec<&FunctionToolCall> {
+        self.output
+            .iter()
+            .filter_map(|item| match item {
+                OutputItem::FunctionCall(call) => Some(call),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns all image generation calls made by the model, in output order.
+    pub fn images(&self) -> Vec<&ImageGenerationCall> {
+        self.output
+            .iter()
+            .filter_map(|item| match item {
+                OutputItem::ImageGenerationCall(image) => Some(image),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Response {
+    type Err = OapiError;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        Ok(serde_json::from_str(content)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_output() {
+        let json = r#"{
+            "id": "resp_67ccd2bed1ec8190b14f964abc0542670bb6a6b452d3795b",
+            "object": "response",
+            "created_at": 1741476542,
+            "status": "completed",
+            "model": "gpt-4.1-2025-04-14",
+            "output": [
+                {
+                    "type": "reasoning",
+                    "id": "rs_67ccd2bf17f0819081ff3bb2cf6508e6",
+                    "summary": []
+                },
+                {
+                    "type": "message",
+                    "id": "msg_67ccd2bf17f0819081ff3bb2cf6508e6",
+                    "status": "completed",
+                    "role": "assistant",
+                    "content": [
+                        {
+                            "type": "output_text",
+                            "text": "In a peaceful grove beneath a silver moon...",
+                            "annotations": []
+                        }
+                    ]
+                }
+            ],
+            "usage": {
+                "input_tokens": 36,
+                "input_tokens_details": { "cached_tokens": 0 },
+                "output_tokens": 87,
+                "output_tokens_details": { "reasoning_tokens": 0 },
+                "total_tokens": 123
+            }
+        }"#;
+
+        let response = Response::from_str(json).unwrap();
+        assert_eq!(response.status, Some(ResponseStatus::Completed));
+        assert_eq!(
+            response.output_text(),
+            "In a peaceful grove beneath a silver moon..."
+        );
+        assert!(response.function_calls().is_empty());
+        assert!(response.images().is_empty());
+    }
+
+    #[test]
+    fn function_call_output() {
+        let json = r#"{
+            "id": "resp_67ca09c5efe0819096d0511c92b8c890096610f474011cc0",
+            "object": "response",
+            "created_at": 1741294021,
+            "status": "completed",
+            "model": "gpt-4.1-2025-04-14",
+            "output": [
+                {
+                    "type": "function_call",
+                    "id": "fc_67ca09c6bedc8190a7abfec07b1a1332096610f474011cc0",
+                    "call_id": "call_unLAR8MvFNptuiZK6K6HCy5k",
+                    "name": "get_current_weather",
+                    "arguments": "{\"location\":\"Boston, MA\",\"unit\":\"celsius\"}",
+                    "status": "completed"
+                },
+                {
+                    "type": "web_search_call",
+                    "id": "ws_67ca09c6bedc8190a7abfec07b1a1332",
+                    "status": "completed"
+                }
+            ]
+        }"#;
+
+        let response = Response::from_str(json).unwrap();
+        let calls = response.function_calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "get_current_weather");
+        assert_eq!(calls[0].call_id, "call_unLAR8MvFNptuiZK6K6HCy5k");
+        assert!(matches!(response.output[1], OutputItem::Other(_)));
+        assert_eq!(response.output_text(), "");
+    }
+}
@@ -0,0 +1,120 @@
+//! Blocking requests, for programs that do not run an async runtime. Enabled
+//! by the `blocking` feature.
+//!
+//! [`NoStream::get_response_blocking`](super::post::NoStream::get_response_blocking)
+//! and [`Stream::get_stream_response_blocking`](super::post::Stream::get_stream_response_blocking)
+//! drive the async requests on a small runtime owned by the crate, and a
+//! [`BlockingStream`] reads a streaming response as an [`Iterator`].
+//!
+//! Blocking calls must not be made from inside an async runtime, where they
+//! panic. Use the async methods there.
+
+use std::{future::Future, str::FromStr, sync::LazyLock};
+
+use futures_util::StreamExt;
+use tokio::runtime::Runtime;
+
+use super::post::ResponseStream;
+use crate::errors::OapiError;
+
+/// The runtime behind every blocking call.
+///
+/// One worker thread keeps the connections of the shared HTTP client alive
+/// between calls, so they are reused like in async code.
+static RUNTIME: LazyLock<Runtime> = LazyLock::new(|| {
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("openai-interface-blocking")
+        .enable_all()
+        .build()
+        .expect("failed to build the runtime of the blocking requests")
+});
+
+/// Runs `future` to completion on the runtime of the blocking requests.
+///
+/// # Panics
+///
+/// When called from inside an async runtime.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    RUNTIME.block_on(future)
+}
+
+/// A streaming response read as an [`Iterator`] of parsed events, ending at the
+/// `[DONE]` sentinel.
+///
+/// Each call to [`next`](Iterator::next) blocks until the next event arrives.
+/// Like a [`ResponseStream`], dropping it closes the connection.
+pub struct BlockingStream<T> {
+    stream: ResponseStream<T>,
+}
+
+impl<T> BlockingStream<T> {
+    pub(crate) fn new(stream: ResponseStream<T>) -> Self {
+        Self { stream }
+    }
+
+    /// Whether the stream ended with the `[DONE]` sentinel, see
+    /// [`ResponseStream::received_done`].
+    pub fn received_done(&self) -> bool {
+        self.stream.received_done()
+    }
+}
+
+impl<T: FromStr<Err = OapiError>> Iterator for BlockingStream<T> {
+    type Item = Result<T, OapiError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        block_on(self.stream.next())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chat::request::{Message, RequestBody};
+    use crate::rest::post::{NoStream, Stream};
+    use crate::test_support::{MockResponse, MockServer};
+
+    use super::*;
+
+    fn request(stream: bool) -> RequestBody {
+        RequestBody {
+            messages: vec![Message::User {
+                content: "Hi".into(),
+                name: None,
+            }],
+            model: "deepseek-chat".to_string(),
+            stream,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn response_blocking() {
+        let server = block_on(MockServer::start(vec![MockResponse::json(
+            200,
+            r#"{"id":"1","object":"chat.completion","created":1,"model":"deepseek-chat","choices":[{"index":0,"message":{"role":"assistant","content":"Hi"},"finish_reason":"stop"}]}"#,
+        )]));
+
+        let completion = request(false)
+            .get_response_blocking(&server.url("/chat/completions"), "key")
+            .unwrap();
+        assert_eq!(completion.choices[0].message.content.as_deref(), Some("Hi"));
+    }
+
+    #[test]
+    fn stream_response_blocking() {
+        let server = block_on(MockServer::start(vec![MockResponse::sse([
+            r#"{"id":"1","choices":[{"index":0,"delta":{"content":"Hi","role":"assistant"},"finish_reason":null}],"created":1,"model":"deepseek-chat","object":"chat.completion.chunk"}"#,
+            r#"{"id":"1","choices":[{"index":0,"delta":{"content":"!"},"finish_reason":"stop"}],"created":1,"model":"deepseek-chat","object":"chat.completion.chunk"}"#,
+            "[DONE]",
+        ])]));
+
+        let mut stream = request(true)
+            .get_stream_response_blocking(&server.url("/chat/completions"), "key")
+            .unwrap();
+        let chunks: Vec<_> = stream.by_ref().map(|chunk| chunk.unwrap()).collect();
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[1].choices[0].finish_reason.is_some());
+        assert!(stream.received_done());
+    }
+}
@@ -0,0 +1,803 @@
+//! A typed client holding the connection settings shared by every request.
+
+use std::{future::Future, sync::Arc, time::Duration};
+
+use futures_util::{StreamExt, stream::BoxStream};
+use reqwest::header::HeaderMap;
+use tokio::time::Instant;
+
+use super::post::{
+    NoStream, Post, ResponseStream, Stream, http_client, parse_response, send_stream_request,
+};
+use super::rate_limit::{RateLimitInfo, RateLimiter, RateLimits};
+use super::retry::RetryPolicy;
+use crate::errors::OapiError;
+
+/// A client for an OpenAI-compatible provider.
+///
+/// The client stores the base URL and the API key, so requests only name the
+/// endpoint path. Clones share the same rate limiter, and every request of the
+/// crate shares one connection pool, so connections to the provider are kept
+/// alive between calls. [`Client::with_http_client`] replaces that pool with
+/// your own `reqwest::Client`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use openai_interface::chat::request::{Message, RequestBody};
+/// use openai_interface::rest::Client;
+/// use openai_interface::rest::rate_limit::RateLimits;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = Client::new("https://api.deepseek.com", "sk-...").with_rate_limits(
+///         RateLimits {
+///             requests_per_minute: Some(60),
+///             ..Default::default()
+///         },
+///     );
+///
+///     let request = RequestBody {
+///         messages: vec![Message::User {
+///             content: "Hello!".into(),
+///             name: None,
+///         }],
+///         model: "deepseek-chat".to_string(),
+///         stream: false,
+///         ..Default::default()
+///     };
+///
+///     let completion = client.post_no_stream("/chat/completions", &request).await?;
+///     println!("{:?}", completion.choices[0].message.content);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Client {
+    base_url: String,
+    api_key: String,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    timeouts: Timeouts,
+    headers: HeaderMap,
+    retry_policy: Option<RetryPolicy>,
+    http: reqwest::Client,
+}
+
+/// Timeouts of a [`Client`]. `None` leaves that timeout unlimited.
+///
+/// Streaming and non-streaming requests are timed separately. A non-streaming
+/// request waits for the whole completion at once, so one total timeout fits
+/// it. A stream may legitimately run for minutes, but a long silence between
+/// two events means the connection is stuck, so it gets a short idle timeout
+/// and a long total one. A request exceeding a timeout fails with
+/// [`OapiError::Timeout`].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Timeouts {
+    /// The longest a non-streaming request may take, from sending it to
+    /// receiving the whole response. Does not apply to streams.
+    pub request_timeout: Option<Duration>,
+    /// The longest a stream may wait for its response headers or for its next
+    /// event.
+    pub stream_idle_timeout: Option<Duration>,
+    /// The longest a stream may take, from sending the request to its last
+    /// event.
+    pub stream_total_timeout: Option<Duration>,
+}
+
+impl Client {
+    /// Creates a client for the provider at `base_url`, e.g. `https://api.deepseek.com`.
+    pub fn new(base_url: impl Into<String>, api_key: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            api_key: api_key.into(),
+            rate_limiter: None,
+            timeouts: Timeouts::default(),
+            headers: HeaderMap::new(),
+            retry_policy: None,
+            http: http_client().clone(),
+        }
+    }
+
+    /// Sends the requests of this client with `http` instead of the client
+    /// shared by the crate, e.g. one configured with a proxy, custom TLS roots
+    /// or its own connection pool.
+    ///
+    /// The timeouts of `http` apply in addition to the [`Timeouts`] of this
+    /// client.
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Sends every request of this client through the proxy at `proxy`, e.g.
+    /// `http://proxy.example.com:8080` or `socks5://127.0.0.1:1080`.
+    ///
+    /// SOCKS proxies need the `socks` feature. Without a proxy set here, the
+    /// `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY` and `NO_PROXY` environment
+    /// variables are honoured, as by any `reqwest::Client`. For more settings,
+    /// build your own client and pass it to [`Client::with_http_client`].
+    pub fn with_proxy(self, proxy: &str) -> Result<Self, OapiError> {
+        if proxy.starts_with("socks") && !cfg!(feature = "socks") {
+            return Err(OapiError::InvalidParameter(format!(
+                "the SOCKS proxy `{}` needs the `socks` feature",
+                proxy
+            )));
+        }
+        let proxy = reqwest::Proxy::all(proxy)
+            .map_err(|e| OapiError::InvalidParameter(format!("invalid proxy: {}", e)))?;
+        let http = reqwest::Client::builder()
+            .proxy(proxy)
+            .build()
+            .map_err(|e| OapiError::InvalidParameter(format!("invalid proxy: {}", e)))?;
+        Ok(self.with_http_client(http))
+    }
+
+    /// Limits the requests sent through this client and its clones. The
+    /// `x-ratelimit-*` headers of every response lower the limits further when
+    /// the provider has less budget left.
+    pub fn with_rate_limits(mut self, limits: RateLimits) -> Self {
+        self.rate_limiter = Some(Arc::new(RateLimiter::new(limits)));
+        self
+    }
+
+    /// Sets the timeouts of requests sent through this client.
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Sends requests through this client again when they fail with a
+    /// transient error, see [`RetryPolicy`]. Without a policy, every error is
+    /// returned at once.
+    ///
+    /// A streaming request is only retried until the stream starts; an error in
+    /// the middle of the stream ends it. Each attempt waits for the rate limits
+    /// and has its own request timeout, while the total timeout of a stream
+    /// covers all of its attempts.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Adds `headers` to every request sent through this client, such as
+    /// `X-Title` and `HTTP-Referer` for OpenRouter.
+    ///
+    /// Headers added by an earlier call are kept, unless `headers` sets them
+    /// again. They are merged onto the default headers as described in
+    /// [`request_headers`](super::post::request_headers): an `Authorization`
+    /// among them does not replace the API key of the client.
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// Returns the base URL the endpoint paths are joined to, as given to
+    /// [`Client::new`].
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Returns the URL for an endpoint path.
+    ///
+    /// The base URL and the path are joined by exactly one slash, whether or not
+    /// the base URL ends with one or the path starts with one, and repeated
+    /// slashes inside the path are collapsed. A trailing slash on the path is
+    /// kept, since some gateways route `/files` and `/files/` differently.
+    ///
+    /// The trait methods in [`post`](super::post) take a full URL and send it
+    /// untouched.
+    pub fn url(&self, path: &str) -> String {
+        join_url(&self.base_url, path)
+    }
+
+    /// Sends a non-streaming request to the endpoint of its
+    /// [`path`](super::post::Post::path), e.g. `/chat/completions` for a chat
+    /// request.
+    ///
+    /// Fails with [`OapiError::InvalidParameter`] for a request without a
+    /// path, which must be sent with [`Client::post_no_stream`].
+    pub async fn send_no_stream<R: NoStream>(&self, request: &R) -> Result<R::Response, OapiError> {
+        self.post_no_stream(&request_path(request)?, request).await
+    }
+
+    /// Sends a streaming request to the endpoint of its
+    /// [`path`](super::post::Post::path), like [`Client::send_no_stream`].
+    pub async fn send_stream<R: Stream>(
+        &self,
+        request: &R,
+    ) -> Result<BoxStream<'static, Result<R::Response, OapiError>>, OapiError> {
+        self.post_stream(&request_path(request)?, request).await
+    }
+
+    /// Sends a non-streaming request to the endpoint at `path`.
+    pub async fn post_no_stream<R: NoStream>(
+        &self,
+        path: &str,
+        request: &R,
+    ) -> Result<R::Response, OapiError> {
+        self.post_no_stream_at(&self.url(path), &self.api_key, request)
+            .await
+    }
+
+    /// Sends a non-streaming request to the full `url` with `key`, instead of
+    /// the base URL and key of this client, e.g. to fall back to another
+    /// provider for one call. The rate limits of this client still apply.
+    pub async fn post_no_stream_at<R: NoStream>(
+        &self,
+        url: &str,
+        key: &str,
+        request: &R,
+    ) -> Result<R::Response, OapiError> {
+        self.with_retries(|| async {
+            self.wait_for_rate_limit(request).await;
+            let response = async {
+                let response = request
+                    .get_raw_response(&self.http, url, key, &self.headers)
+                    .await?;
+                self.update_rate_limits(response.headers());
+                parse_response(response).await
+            };
+            match self.timeouts.request_timeout {
+                Some(timeout) => tokio::time::timeout(timeout, response)
+                    .await
+                    .map_err(|_| OapiError::Timeout(format!("no response within {:?}", timeout)))?,
+                None => response.await,
+            }
+        })
+        .await
+    }
+
+    /// Sends a streaming request to the endpoint at `path`.
+    pub async fn post_stream<R: Stream>(
+        &self,
+        path: &str,
+        request: &R,
+    ) -> Result<BoxStream<'static, Result<R::Response, OapiError>>, OapiError> {
+        self.post_stream_at(&self.url(path), &self.api_key, request)
+            .await
+    }
+
+    /// Sends a streaming request to the full `url` with `key`, like
+    /// [`Client::post_no_stream_at`].
+    pub async fn post_stream_at<R: Stream>(
+        &self,
+        url: &str,
+        key: &str,
+        request: &R,
+    ) -> Result<BoxStream<'static, Result<R::Response, OapiError>>, OapiError> {
+        let idle = self.timeouts.stream_idle_timeout;
+        let deadline = self
+            .timeouts
+            .stream_total_timeout
+            .map(|timeout| (Instant::now() + timeout, timeout));
+
+        let stream = self
+            .with_retries(|| async {
+                self.wait_for_rate_limit(request).await;
+                let response = within(
+                    send_stream_request(&self.http, request, url, key, &self.headers),
+                    idle,
+                    deadline,
+                )
+                .await??;
+                self.update_rate_limits(response.headers());
+                let stream = ResponseStream::<R::Response>::new(response.into());
+                Ok::<_, OapiError>(Box::pin(stream) as BoxStream<'static, _>)
+            })
+            .await?;
+        if idle.is_none() && deadline.is_none() {
+            return Ok(stream);
+        }
+
+        let stream = futures_util::stream::unfold(Some(stream), move |stream| async move {
+            let mut stream = stream?;
+            match within(stream.next(), idle, deadline).await {
+                Ok(Some(item)) => Some((item, Some(stream))),
+                Ok(None) => None,
+                // Nothing follows a timeout.
+                Err(e) => Some((Err(e), None)),
+            }
+        });
+        Ok(Box::pin(stream))
+    }
+
+    /// Runs `send` until it succeeds, fails with an error that is not
+    /// retryable, or runs out of attempts.
+    async fn with_retries<T, F, Fut>(&self, mut send: F) -> Result<T, OapiError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, OapiError>>,
+    {
+        let Some(policy) = self.retry_policy else {
+            return send().await;
+        };
+
+        let mut attempt = 1;
+        loop {
+            match send().await {
+                Err(e) if RetryPolicy::is_retryable(&e) => {
+                    if attempt >= policy.max_attempts {
+                        return Err(OapiError::RetriesExhausted {
+                            attempts: attempt,
+                            last: Box::new(e),
+                        });
+                    }
+                    tokio::time::sleep(policy.delay(attempt, &e)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn wait_for_rate_limit<R: serde::Serialize>(&self, request: &R) {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(estimate_tokens(request)).await;
+        }
+    }
+
+    /// Feeds the `x-ratelimit-*` headers of a response back into the rate
+    /// limiter, see [`RateLimiter::update`].
+    fn update_rate_limits(&self, headers: &HeaderMap) {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.update(&RateLimitInfo::from_headers(headers));
+        }
+    }
+}
+
+/// Awaits `future` for at most the idle timeout, and at most until the
+/// deadline of the stream.
+async fn within<F: Future>(
+    future: F,
+    idle: Option<Duration>,
+    deadline: Option<(Instant, Duration)>,
+) -> Result<F::Output, OapiError> {
+    let remaining = deadline
+        .map(|(deadline, total)| (deadline.saturating_duration_since(Instant::now()), total));
+    let (wait, error) = match (idle, remaining) {
+        (None, None) => return Ok(future.await),
+        (Some(idle), Some((remaining, _))) if idle < remaining => {
+            (idle, format!("no stream event within {:?}", idle))
+        }
+        (Some(idle), None) => (idle, format!("no stream event within {:?}", idle)),
+        (_, Some((remaining, total))) => (
+            remaining,
+            format!("the stream exceeded its total timeout of {:?}", total),
+        ),
+    };
+    tokio::time::timeout(wait, future)
+        .await
+        .map_err(|_| OapiError::Timeout(error))
+}
+
+fn request_path<R: Post + ?Sized>(request: &R) -> Result<String, OapiError> {
+    request.path().ok_or_else(|| {
+        OapiError::InvalidParameter(format!(
+            "`{}` has no known endpoint path; send it with a path instead",
+            std::any::type_name::<R>()
+        ))
+    })
+}
+
+pub(crate) fn join_url(base_url: &str, path: &str) -> String {
+    let base_url = base_url.trim_end_matches('/');
+    let path = path.trim_start_matches('/');
+    if path.is_empty() {
+        return base_url.to_string();
+    }
+
+    let mut url = String::with_capacity(base_url.len() + path.len() + 1);
+    url.push_str(base_url);
+    for c in std::iter::once('/').chain(path.chars()) {
+        if !(c == '/' && url.ends_with('/')) {
+            url.push(c);
+        }
+    }
+    url
+}
+
+/// Roughly estimates the prompt tokens of a request from the size of its body,
+/// at about four bytes per token.
+fn estimate_tokens<R: serde::Serialize>(request: &R) -> u32 {
+    let bytes = serde_json::to_vec(request)
+        .map(|body| body.len())
+        .unwrap_or(0);
+    (bytes / 4) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat::request::RequestBody;
+    use crate::test_support::{MockResponse, MockServer};
+
+    const COMPLETION: &str = r#"{"id":"1","object":"chat.completion","created":1,"model":"deepseek-chat","choices":[{"index":0,"message":{"role":"assistant","content":"Hi"},"finish_reason":"stop"}]}"#;
+
+    #[test]
+    fn url_joining() {
+        for base_url in ["https://api.deepseek.com", "https://api.deepseek.com/"] {
+            let client = Client::new(base_url, "key");
+            assert_eq!(
+                client.url("/chat/completions"),
+                "https://api.deepseek.com/chat/completions"
+            );
+            assert_eq!(
+                client.url("chat/completions"),
+                "https://api.deepseek.com/chat/completions"
+            );
+            assert_eq!(
+                client.url("//chat//completions"),
+                "https://api.deepseek.com/chat/completions"
+            );
+            assert_eq!(client.url("/files/"), "https://api.deepseek.com/files/");
+            assert_eq!(client.url(""), "https://api.deepseek.com");
+        }
+
+        let client = Client::new("https://dashscope.aliyuncs.com/compatible-mode/v1/", "key");
+        assert_eq!(
+            client.url("/completions"),
+            "https://dashscope.aliyuncs.com/compatible-mode/v1/completions"
+        );
+    }
+
+    #[tokio::test]
+    async fn send_to_request_path() {
+        let server = MockServer::start(vec![MockResponse::json(200, COMPLETION)]).await;
+
+        let client = Client::new(server.url("/v1/"), "key");
+        let request = RequestBody {
+            model: "deepseek-chat".to_string(),
+            ..Default::default()
+        };
+        client.send_no_stream(&request).await.unwrap();
+        assert_eq!(server.requests()[0].path, "/v1/chat/completions");
+
+        let upload_part = crate::uploads::request::AddUploadPartRequest::default();
+        assert!(matches!(
+            client.send_no_stream(&upload_part).await,
+            Err(OapiError::InvalidParameter(_))
+        ));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn override_url_and_key() {
+        let primary = MockServer::start(vec![MockResponse::json(200, COMPLETION)]).await;
+        let fallback = MockServer::start(vec![MockResponse::json(200, COMPLETION)]).await;
+
+        let client = Client::new(primary.url("/v1"), "primary-key");
+        let request = RequestBody {
+            model: "deepseek-chat".to_string(),
+            ..Default::default()
+        };
+        client
+            .post_no_stream_at(
+                &fallback.url("/v1/chat/completions"),
+                "fallback-key",
+                &request,
+            )
+            .await
+            .unwrap();
+
+        assert!(primary.requests().is_empty());
+        let requests = fallback.requests();
+        assert_eq!(requests[0].path, "/v1/chat/completions");
+        assert_eq!(
+            requests[0].header("authorization"),
+            Some("Bearer fallback-key")
+        );
+    }
+
+    #[tokio::test]
+    async fn extra_headers() {
+        let server = MockServer::start(vec![MockResponse::json(200, COMPLETION)]).await;
+
+        let mut title = HeaderMap::new();
+        title.insert("X-Title", "My App".parse().unwrap());
+        let mut authorization = HeaderMap::new();
+        authorization.insert("Authorization", "Basic dXNlcjpwYXNz".parse().unwrap());
+        let client = Client::new(server.url("/v1"), "key")
+            .with_headers(title)
+            .with_headers(authorization);
+        let request = RequestBody {
+            model: "deepseek-chat".to_string(),
+            ..Default::default()
+        };
+        client
+            .post_no_stream("/chat/completions", &request)
+            .await
+            .unwrap();
+        // With an empty key, the Authorization of the extra headers is sent.
+        client
+            .post_no_stream_at(&server.url("/v1/chat/completions"), "", &request)
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests[0].header("x-title"), Some("My App"));
+        assert_eq!(requests[0].header("authorization"), Some("Bearer key"));
+        assert_eq!(requests[0].header("content-type"), Some("application/json"));
+        assert_eq!(
+            requests[1].header("authorization"),
+            Some("Basic dXNlcjpwYXNz")
+        );
+    }
+
+    #[tokio::test]
+    async fn custom_http_client() {
+        let server = MockServer::start(vec![
+            MockResponse::json(200, COMPLETION),
+            MockResponse::sse([
+                r#"{"id":"1","object":"chat.completion.chunk","created":1,"model":"deepseek-chat","choices":[]}"#,
+                "[DONE]",
+            ]),
+        ])
+        .await;
+
+        let http = reqwest::Client::builder()
+            .user_agent("my-proxy-aware-app")
+            .build()
+            .unwrap();
+        let client = Client::new(server.url("/v1"), "key").with_http_client(http.clone());
+        let mut request = RequestBody {
+            model: "deepseek-chat".to_string(),
+            ..Default::default()
+        };
+        client
+            .post_no_stream("/chat/completions", &request)
+            .await
+            .unwrap();
+
+        request.stream = true;
+        let chunks: Vec<_> = request
+            .get_stream_response_with_client(&http, &server.url("/v1/chat/completions"), "key")
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(chunks.len(), 1);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        for recorded in requests {
+            assert_eq!(recorded.header("user-agent"), Some("my-proxy-aware-app"));
+        }
+    }
+
+    #[tokio::test]
+    async fn through_proxy() {
+        let proxy = MockServer::start(vec![MockResponse::json(200, COMPLETION)]).await;
+
+        let client = Client::new("http://api.example.invalid/v1", "key")
+            .with_proxy(&proxy.url(""))
+            .unwrap();
+        let request = RequestBody {
+            model: "deepseek-chat".to_string(),
+            ..Default::default()
+        };
+        client
+            .post_no_stream("/chat/completions", &request)
+            .await
+            .unwrap();
+
+        // An HTTP proxy receives the absolute URL of the target.
+        assert_eq!(
+            proxy.requests()[0].path,
+            "http://api.example.invalid/v1/chat/completions"
+        );
+
+        assert!(matches!(
+            Client::new("https://api.deepseek.com", "key").with_proxy("not a url"),
+            Err(OapiError::InvalidParameter(_))
+        ));
+        assert_eq!(
+            Client::new("https://api.deepseek.com", "key")
+                .with_proxy("socks5://127.0.0.1:1080")
+                .is_ok(),
+            cfg!(feature = "socks")
+        );
+    }
+
+    #[tokio::test]
+    async fn rate_limits_follow_response_headers() {
+        let server = MockServer::start(vec![
+            MockResponse::json(200, COMPLETION)
+                .with_header("x-ratelimit-remaining-requests", "0")
+                .with_header("x-ratelimit-reset-requests", "300ms"),
+            MockResponse::json(200, COMPLETION),
+        ])
+        .await;
+        let client = Client::new(server.url(""), "key").with_rate_limits(RateLimits {
+            requests_per_minute: Some(60_000),
+            ..Default::default()
+        });
+        let request = RequestBody {
+            model: "deepseek-chat".to_string(),
+            ..Default::default()
+        };
+
+        client
+            .post_no_stream("/chat/completions", &request)
+            .await
+            .unwrap();
+        let start = Instant::now();
+        client
+            .post_no_stream("/chat/completions", &request)
+            .await
+            .unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(280));
+    }
+
+    #[tokio::test]
+    async fn retry_transient_errors() {
+        let request = RequestBody {
+            model: "deepseek-chat".to_string(),
+            ..Default::default()
+        };
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::ZERO,
+            jitter: 0.0,
+        };
+
+        let server = MockServer::start(vec![
+            MockResponse::json(503, "Service Unavailable"),
+            MockResponse::json(429, "{}").with_header("Retry-After", "0"),
+            MockResponse::json(200, COMPLETION),
+        ])
+        .await;
+        let client = Client::new(server.url(""), "key").with_retry_policy(policy);
+        client
+            .post_no_stream("/chat/completions", &request)
+            .await
+            .unwrap();
+        assert_eq!(server.requests().len(), 3);
+
+        let server = MockServer::start(vec![MockResponse::json(502, "Bad Gateway")]).await;
+        let client = Client::new(server.url(""), "key").with_retry_policy(policy);
+        let error = client
+            .post_no_stream("/chat/completions", &request)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            OapiError::RetriesExhausted { attempts: 3, .. }
+        ));
+        assert_eq!(error.status(), Some(502));
+        assert_eq!(server.requests().len(), 3);
+
+        let server = MockServer::start(vec![MockResponse::json(400, "{}")]).await;
+        let client = Client::new(server.url(""), "key").with_retry_policy(policy);
+        assert!(matches!(
+            client.post_no_stream("/chat/completions", &request).await,
+            Err(OapiError::ApiError(_))
+        ));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    fn chunk_events(n: usize) -> Vec<String> {
+        let mut events: Vec<String> = (0..n)
+            .map(|i| {
+                format!(
+                    r#"{{"id":"1","choices":[{{"index":0,"delta":{{"content":"{}"}},"finish_reason":null}}],"created":1,"model":"deepseek-chat","object":"chat.completion.chunk"}}"#,
+                    i
+                )
+            })
+            .collect();
+        events.push("[DONE]".to_string());
+        events
+    }
+
+    fn streaming_request() -> RequestBody {
+        RequestBody {
+            model: "deepseek-chat".to_string(),
+            stream: true,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn request_timeout_applies_to_non_streaming_only() {
+        let slow = MockServer::start(vec![
+            MockResponse::json(200, COMPLETION).with_delay(Duration::from_millis(300)),
+        ])
+        .await;
+        let events = chunk_events(3);
+        let stream = MockServer::start(vec![
+            MockResponse::sse(events.iter().map(String::as_str))
+                .with_part_delay(Duration::from_millis(100)),
+        ])
+        .await;
+
+        let timeouts = Timeouts {
+            request_timeout: Some(Duration::from_millis(50)),
+            ..Default::default()
+        };
+        let request = RequestBody {
+            model: "deepseek-chat".to_string(),
+            ..Default::default()
+        };
+        let result = Client::new(slow.url(""), "key")
+            .with_timeouts(timeouts)
+            .post_no_stream("/chat/completions", &request)
+            .await;
+        assert!(matches!(result, Err(OapiError::Timeout(_))));
+
+        // The stream takes longer than `request_timeout` but is not cut off.
+        let chunks: Vec<_> = Client::new(stream.url(""), "key")
+            .with_timeouts(timeouts)
+            .post_stream("/chat/completions", &streaming_request())
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(Result::is_ok));
+    }
+
+    #[tokio::test]
+    async fn stream_timeouts_apply_to_streaming_only() {
+        let events = chunk_events(3);
+        let stalled = MockServer::start(vec![
+            MockResponse::sse(events.iter().map(String::as_str))
+                .with_part_delay(Duration::from_millis(1000)),
+        ])
+        .await;
+        let client = Client::new(stalled.url(""), "key").with_timeouts(Timeouts {
+            stream_idle_timeout: Some(Duration::from_millis(400)),
+            ..Default::default()
+        });
+        let chunks: Vec<_> = client
+            .post_stream("/chat/completions", &streaming_request())
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].is_ok());
+        assert!(matches!(&chunks[1], Err(OapiError::Timeout(e)) if e.contains("no stream event")));
+
+        let events = chunk_events(10);
+        let long = MockServer::start(vec![
+            MockResponse::sse(events.iter().map(String::as_str))
+                .with_part_delay(Duration::from_millis(100)),
+        ])
+        .await;
+        let client = Client::new(long.url(""), "key").with_timeouts(Timeouts {
+            stream_idle_timeout: Some(Duration::from_millis(400)),
+            stream_total_timeout: Some(Duration::from_millis(600)),
+            ..Default::default()
+        });
+        let chunks: Vec<_> = client
+            .post_stream("/chat/completions", &streaming_request())
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert!(chunks.len() < 10);
+        assert!(
+            matches!(chunks.last(), Some(Err(OapiError::Timeout(e))) if e.contains("total timeout"))
+        );
+
+        // A slow non-streaming response is not subject to the stream timeouts.
+        let slow = MockServer::start(vec![
+            MockResponse::json(200, COMPLETION).with_delay(Duration::from_millis(300)),
+        ])
+        .await;
+        let client = Client::new(slow.url(""), "key").with_timeouts(Timeouts {
+            stream_idle_timeout: Some(Duration::from_millis(50)),
+            stream_total_timeout: Some(Duration::from_millis(50)),
+            ..Default::default()
+        });
+        let request = RequestBody {
+            model: "deepseek-chat".to_string(),
+            ..Default::default()
+        };
+        assert!(
+            client
+                .post_no_stream("/chat/completions", &request)
+                .await
+                .is_ok()
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use std::{future::Future, str::FromStr};
+
+use serde::de::DeserializeOwned;
+
+use super::get::send_request;
+use crate::errors::OapiError;
+
+/// Sends DELETE requests for resources, such as an uploaded file.
+pub trait Delete: Sync + Send {
+    type Response: DeserializeOwned + FromStr<Err = OapiError> + Send + Sync;
+
+    /// The URL of the resource, given the URL of the endpoint it belongs to,
+    /// e.g. `/files/{file_id}` for `/files`.
+    fn url(&self, endpoint: &str) -> String;
+
+    /// Sends a DELETE request to the resource at `url` with the provided api-key.
+    fn get_response_string(
+        &self,
+        url: &str,
+        key: &str,
+    ) -> impl Future<Output = Result<String, OapiError>> + Send + Sync {
+        async move {
+            let response = send_request(reqwest::Method::DELETE, &self.url(url), key, &[]).await?;
+            Ok(response.text().await?)
+        }
+    }
+
+    fn get_response(
+        &self,
+        url: &str,
+        key: &str,
+    ) -> impl Future<Output = Result<Self::Response, OapiError>> + Send + Sync {
+        async move {
+            let text = self.get_response_string(url, key).await?;
+            Self::Response::from_str(&text)
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use std::{future::Future, str::FromStr};
+
+use serde::de::DeserializeOwned;
+
+use super::post::{http_client, send_checked};
+use crate::errors::OapiError;
+
+/// Sends GET requests for resources answered with one JSON response, such as
+/// the model list.
+///
+/// Like [`NoStream`](super::post::NoStream), these methods wait for the
+/// provider without a bound.
+pub trait Get: Sync + Send {
+    type Response: DeserializeOwned + FromStr<Err = OapiError> + Send + Sync;
+
+    /// The URL of the resource, given the URL of the endpoint it belongs to.
+    ///
+    /// Defaults to the endpoint itself. Requests for one resource append its
+    /// ID, e.g. `/models/{model}`.
+    fn url(&self, endpoint: &str) -> String {
+        endpoint.to_string()
+    }
+
+    /// The query parameters of the request, such as filters of a list
+    /// endpoint. Defaults to none.
+    fn query(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    /// Sends a GET request to the resource at `url` with the provided api-key.
+    fn get_response_string(
+        &self,
+        url: &str,
+        key: &str,
+    ) -> impl Future<Output = Result<String, OapiError>> + Send + Sync {
+        async move {
+            let response =
+                send_request(reqwest::Method::GET, &self.url(url), key, &self.query()).await?;
+            Ok(response.text().await?)
+        }
+    }
+
+    fn get_response(
+        &self,
+        url: &str,
+        key: &str,
+    ) -> impl Future<Output = Result<Self::Response, OapiError>> + Send + Sync {
+        async move {
+            let text = self.get_response_string(url, key).await?;
+            Self::Response::from_str(&text)
+        }
+    }
+}
+
+/// Sends a request without a body and checks the response status.
+pub(crate) async fn send_request(
+    method: reqwest::Method,
+    url: &str,
+    key: &str,
+    query: &[(&str, String)],
+) -> Result<reqwest::Response, OapiError> {
+    send_checked(
+        http_client()
+            .request(method, url)
+            .bearer_auth(key)
+            .query(query),
+    )
+    .await
+}
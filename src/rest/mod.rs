@@ -6,7 +6,17 @@
 //! # Overview
 //!
 //! The `rest` module contains:
+//! - `blocking`: Blocking requests for programs without an async runtime, behind the
+//!   `blocking` feature
 //! - [`post`]: HTTP POST request functionality with streaming and non-streaming support
+//! - [`get`]: HTTP GET request functionality for resources such as the model list
+//! - [`delete`]: HTTP DELETE request functionality for resources such as files
+//! - [`client`]: A typed [`Client`] holding the base URL, API key, rate limits, timeouts
+//!   and proxy, with SOCKS proxies behind the `socks` feature
+//! - [`pagination`]: Streaming every item of a cursor-paginated list endpoint
+//! - [`rate_limit`]: A client-side token bucket rate limiter
+//! - [`retry`]: Retrying rate-limited and overloaded requests with backoff
+//! - `replay`: Recording and replaying interactions for tests, behind the `replay` feature
 //! - Traits for defining API request behavior
 //! - Error handling for HTTP communication
 //!
@@ -38,9 +48,7 @@
 //!     type Err = OapiError;
 //!
 //!     fn from_str(content: &str) -> Result<Self, Self::Err> {
-//!         let parse_result: Result<Self, _> = serde_json::from_str(content)
-//!             .map_err(|e| OapiError::DeserializationError(e.to_string()));
-//!         parse_result
+//!         Ok(serde_json::from_str(content)?)
 //!     }
 //! }
 //!
@@ -56,4 +64,16 @@
 //! // or impl Stream for MyRequest {} for streaming requests
 //! ```
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod client;
+pub mod delete;
+pub mod get;
+pub mod pagination;
 pub mod post;
+pub mod rate_limit;
+#[cfg(feature = "replay")]
+pub mod replay;
+pub mod retry;
+
+pub use client::Client;
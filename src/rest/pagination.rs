@@ -0,0 +1,189 @@
+//! Cursor pagination shared by the list endpoints.
+//!
+//! List endpoints such as files, batches and fine-tuning jobs return one
+//! [`Page`] at a time. The next page is requested with `after` set to the ID of
+//! the last item, until `has_more` is `false`. [`paginate`] does this and
+//! yields the items one by one.
+
+use std::{collections::VecDeque, future::Future, str::FromStr};
+
+use futures_util::Stream;
+use serde::{Deserialize, de::DeserializeOwned};
+
+use crate::errors::OapiError;
+
+/// One page of a list endpoint.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Page<T> {
+    /// The items of this page.
+    pub data: Vec<T>,
+    /// Whether there are more items after this page.
+    #[serde(default)]
+    pub has_more: bool,
+    /// The ID of the first item of this page, if the endpoint returns it.
+    pub first_id: Option<String>,
+    /// The ID of the last item of this page, if the endpoint returns it.
+    pub last_id: Option<String>,
+}
+
+impl<T: DeserializeOwned> FromStr for Page<T> {
+    type Err = OapiError;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(content).map_err(OapiError::from)
+    }
+}
+
+/// An item of a list endpoint, identified by the ID used as a cursor.
+pub trait PageItem {
+    fn id(&self) -> &str;
+}
+
+/// Streams every item of a list endpoint.
+///
+/// `fetch` requests one page. It receives `None` for the first page, and the
+/// `after` cursor of the next page afterwards. The stream ends after the last
+/// page, or after the first error.
+///
+/// # Example
+///
+/// ```rust
+/// use futures_util::StreamExt;
+/// use openai_interface::errors::OapiError;
+/// use openai_interface::rest::pagination::{Page, PageItem, paginate};
+///
+/// struct Model {
+///     id: String,
+/// }
+///
+/// impl PageItem for Model {
+///     fn id(&self) -> &str {
+///         &self.id
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let models: Vec<String> = paginate(|after: Option<String>| async move {
+///         // Request the page after `after` here.
+///         let page = Page {
+///             data: vec![Model { id: "deepseek-chat".to_string() }],
+///             has_more: false,
+///             first_id: None,
+///             last_id: None,
+///         };
+///         Ok::<_, OapiError>(page)
+///     })
+///     .map(|model| model.unwrap().id)
+///     .collect()
+///     .await;
+///
+///     assert_eq!(models, vec!["deepseek-chat"]);
+/// }
+/// ```
+pub fn paginate<T, F, Fut>(fetch: F) -> impl Stream<Item = Result<T, OapiError>>
+where
+    T: PageItem,
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<Page<T>, OapiError>>,
+{
+    let state = State {
+        fetch,
+        items: VecDeque::new(),
+        next: Some(None),
+    };
+
+    futures_util::stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(item) = state.items.pop_front() {
+                return Some((Ok(item), state));
+            }
+
+            let after = state.next.take()?;
+            match (state.fetch)(after).await {
+                Ok(page) => {
+                    let cursor = page
+                        .last_id
+                        .or_else(|| page.data.last().map(|item| item.id().to_string()));
+                    if page.has_more {
+                        // Without a cursor the same page would be fetched forever.
+                        state.next = cursor.map(Some);
+                    }
+                    state.items.extend(page.data);
+                }
+                Err(e) => return Some((Err(e), state)),
+            }
+        }
+    })
+}
+
+struct State<T, F> {
+    fetch: F,
+    items: VecDeque<T>,
+    /// The cursor of the next page to fetch, or `None` after the last page.
+    next: Option<Option<String>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+
+    use super::*;
+    use crate::test_support::{MockResponse, MockServer};
+
+    #[derive(Debug, Deserialize)]
+    struct Item {
+        id: String,
+    }
+
+    impl PageItem for Item {
+        fn id(&self) -> &str {
+            &self.id
+        }
+    }
+
+    #[tokio::test]
+    async fn two_pages() {
+        let server = MockServer::start(vec![
+            MockResponse::json(
+                200,
+                r#"{"object":"list","data":[{"id":"file-1"},{"id":"file-2"}],"has_more":true}"#,
+            ),
+            MockResponse::json(
+                200,
+                r#"{"object":"list","data":[{"id":"file-3"}],"has_more":false}"#,
+            ),
+        ])
+        .await;
+        let url = server.url("/files");
+
+        let ids: Vec<String> = paginate(|after: Option<String>| {
+            let request = reqwest::Client::new().get(&url);
+            let request = match after {
+                Some(after) => request.query(&[("after", after)]),
+                None => request,
+            };
+            async move {
+                let text = request.send().await?.text().await?;
+                Ok::<_, OapiError>(serde_json::from_str::<Page<Item>>(&text)?)
+            }
+        })
+        .map(|item| item.unwrap().id)
+        .collect()
+        .await;
+
+        assert_eq!(ids, vec!["file-1", "file-2", "file-3"]);
+        let paths: Vec<String> = server.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!(paths, vec!["/files", "/files?after=file-2"]);
+    }
+
+    #[tokio::test]
+    async fn error_ends_stream() {
+        let results: Vec<Result<Item, OapiError>> =
+            paginate(|_| async { Err::<Page<Item>, _>(OapiError::ResponseStatus(500)) })
+                .collect()
+                .await;
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0], Err(OapiError::ResponseStatus(500))));
+    }
+}
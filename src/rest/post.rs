@@ -1,55 +1,146 @@
-use std::{future::Future, str::FromStr};
+use std::{
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    str::FromStr,
+    sync::LazyLock,
+    task::{Context, Poll},
+};
 
-use eventsource_stream::Eventsource;
-use futures_util::{StreamExt, TryStreamExt, stream::BoxStream};
+use eventsource_stream::{EventStream, Eventsource};
+use futures_util::{StreamExt, stream::BoxStream};
+use http_body_util::BodyDataStream;
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
 use serde::{Serialize, de::DeserializeOwned};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 
-use crate::errors::OapiError;
+use super::rate_limit::RateLimitInfo;
+use crate::errors::{ApiError, OapiError};
+
+/// The HTTP client behind every request of this crate.
+///
+/// Building a `reqwest::Client` sets up a new connection pool and TLS
+/// configuration, so sharing one lets consecutive requests to a provider reuse
+/// their connections instead of repeating the TCP and TLS handshakes.
+static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
+
+/// Returns the shared HTTP client. Clones point to the same connection pool.
+pub(crate) fn http_client() -> &'static reqwest::Client {
+    &HTTP_CLIENT
+}
 
 pub trait Post {
     fn is_streaming(&self) -> bool;
+
+    /// The path of the endpoint the request is sent to, below the base URL of
+    /// a [`Client`](super::Client), e.g. `/chat/completions`.
+    ///
+    /// Used by [`Client::send_no_stream`](super::Client::send_no_stream) and
+    /// [`Client::send_stream`](super::Client::send_stream). Defaults to `None`,
+    /// for requests that do not know their path; send those with
+    /// [`Client::post_no_stream`](super::Client::post_no_stream) instead.
+    fn path(&self) -> Option<String> {
+        None
+    }
+
+    /// Serializes the request to the JSON body that [`NoStream`] and [`Stream`]
+    /// send, for example to compare it with a working `curl` command.
+    ///
+    /// Requests sent as multipart forms, such as file uploads, are not sent as
+    /// this JSON.
+    fn to_json_string(&self) -> Result<String, OapiError>
+    where
+        Self: Serialize,
+    {
+        serde_json::to_string(self).map_err(|e| OapiError::SerializationError(e.to_string()))
+    }
 }
 
+/// Sends requests that are answered with one response.
+///
+/// These methods wait for the provider without a bound. To give up on a hung
+/// provider, send requests through a [`Client`](super::Client) with
+/// [`Timeouts`](super::client::Timeouts), which fail with
+/// [`OapiError::Timeout`].
 pub trait NoStream: Post + Serialize + Sync + Send {
     type Response: DeserializeOwned + FromStr<Err = OapiError> + Send + Sync;
 
-    /// Sends a POST request to the specified URL with the provided api-key.
-    fn get_response_string(
+    /// Sends the request with `client` and returns the response once its status
+    /// is checked.
+    ///
+    /// Every other method sends the request through this one, with the client
+    /// shared by the crate unless they take one. The default posts the request
+    /// as JSON; requests with another body, such as a multipart file upload,
+    /// override it.
+    fn get_raw_response(
         &self,
+        client: &reqwest::Client,
         url: &str,
         key: &str,
-    ) -> impl Future<Output = Result<String, OapiError>> + Send + Sync {
+        headers: &HeaderMap,
+    ) -> impl Future<Output = Result<reqwest::Response, OapiError>> + Send + Sync {
         async move {
             if self.is_streaming() {
                 return Err(OapiError::NonStreamingViolation);
             }
 
-            let client = reqwest::Client::new();
             let response = client
                 .post(url)
-                .headers({
-                    let mut headers = reqwest::header::HeaderMap::new();
-                    headers.insert("Content-Type", "application/json".parse().unwrap());
-                    headers.insert("Accept", "application/json".parse().unwrap());
-                    headers
-                })
-                .bearer_auth(key)
+                .headers(request_headers("application/json", key, headers)?)
                 .json(self)
                 .send()
-                .await
-                .map_err(|e| OapiError::SendError(format!("Failed to send request: {:#?}", e)))?;
+                .await?;
 
-            if response.status() != reqwest::StatusCode::OK {
-                return Err(
-                    crate::errors::OapiError::ResponseStatus(response.status().as_u16()).into(),
-                );
+            if !response.status().is_success() {
+                return Err(ApiError::from_response(response).await.into());
             }
+            Ok(response)
+        }
+    }
+
+    /// Sends a POST request to the specified URL with the provided api-key.
+    fn get_response_string(
+        &self,
+        url: &str,
+        key: &str,
+    ) -> impl Future<Output = Result<String, OapiError>> + Send + Sync {
+        async move {
+            self.get_response_string_with_headers(url, key, &HeaderMap::new())
+                .await
+        }
+    }
 
-            let text = response.text().await.map_err(|e| {
-                OapiError::ResponseError(format!("Failed to get response text: {:#?}", e))
-            })?;
+    /// Like [`NoStream::get_response_string`], with `headers` merged onto the
+    /// default headers, see [`request_headers`].
+    fn get_response_string_with_headers(
+        &self,
+        url: &str,
+        key: &str,
+        headers: &HeaderMap,
+    ) -> impl Future<Output = Result<String, OapiError>> + Send + Sync {
+        async move {
+            let response = self
+                .get_raw_response(http_client(), url, key, headers)
+                .await?;
+            response_text(response).await
+        }
+    }
 
-            Ok(text)
+    /// Like [`NoStream::get_response`], but also returns the rate limits the
+    /// provider reported in the response headers.
+    fn get_response_with_meta(
+        &self,
+        url: &str,
+        key: &str,
+    ) -> impl Future<Output = Result<(Self::Response, RateLimitInfo), OapiError>> + Send + Sync
+    {
+        async move {
+            let response = self
+                .get_raw_response(http_client(), url, key, &HeaderMap::new())
+                .await?;
+            let info = RateLimitInfo::from_headers(response.headers());
+            let text = response_text(response).await?;
+            Ok((Self::Response::from_str(&text)?, info))
         }
     }
 
@@ -64,13 +155,82 @@ pub trait NoStream: Post + Serialize + Sync + Send {
             Ok(result)
         }
     }
+
+    /// Like [`NoStream::get_response`], but sent with `client` instead of the
+    /// client shared by the crate, e.g. one configured with a proxy or custom
+    /// TLS roots.
+    fn get_response_with_client(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        key: &str,
+    ) -> impl Future<Output = Result<Self::Response, OapiError>> + Send + Sync {
+        async move {
+            let response = self
+                .get_raw_response(client, url, key, &HeaderMap::new())
+                .await?;
+            parse_response(response).await
+        }
+    }
+
+    /// Like [`NoStream::get_response`], with `headers` merged onto the default
+    /// headers, see [`request_headers`].
+    fn get_response_with_headers(
+        &self,
+        url: &str,
+        key: &str,
+        headers: &HeaderMap,
+    ) -> impl Future<Output = Result<Self::Response, OapiError>> + Send + Sync {
+        async move {
+            let text = self
+                .get_response_string_with_headers(url, key, headers)
+                .await?;
+            Self::Response::from_str(&text)
+        }
+    }
+
+    /// Like [`NoStream::get_response`], but blocks the current thread until the
+    /// response arrives, for programs without an async runtime. Needs the
+    /// `blocking` feature, see [`blocking`](super::blocking).
+    ///
+    /// # Panics
+    ///
+    /// When called from inside an async runtime.
+    #[cfg(feature = "blocking")]
+    fn get_response_blocking(&self, url: &str, key: &str) -> Result<Self::Response, OapiError> {
+        super::blocking::block_on(self.get_response(url, key))
+    }
+
+    /// Sends the request and returns the response body as untyped JSON.
+    ///
+    /// Useful to inspect a response that [`NoStream::get_response`] fails to
+    /// parse, or fields this crate does not cover yet.
+    fn get_response_value(
+        &self,
+        url: &str,
+        key: &str,
+    ) -> impl Future<Output = Result<serde_json::Value, OapiError>> + Send + Sync {
+        async move {
+            let text = self.get_response_string(url, key).await?;
+            Ok(serde_json::from_str(&text)?)
+        }
+    }
 }
 
+/// Sends requests that are answered with a stream of server-sent events.
+///
+/// Like [`NoStream`], these methods wait without a bound. A
+/// [`Client`](super::Client) can limit both the silence between two events and
+/// the whole stream, see [`Timeouts`](super::client::Timeouts).
 pub trait Stream: Post + Serialize + Sync + Send {
-    type Response: DeserializeOwned + FromStr<Err = OapiError> + Send + Sync;
+    type Response: DeserializeOwned + FromStr<Err = OapiError> + Send + Sync + 'static;
 
     /// Sends a streaming POST request to the specified URL with the provided api-key.
     ///
+    /// Yields the data of every event, unparsed. Like the typed streams, it ends
+    /// at the `[DONE]` sentinel, which is not yielded, and skips the empty
+    /// events some providers send to keep the connection open.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -93,7 +253,7 @@ pub trait Stream: Post + Serialize + Sync + Send {
     ///                 name: None,
     ///             },
     ///             Message::User {
-    ///                 content: "What's your name?".to_string(),
+    ///                 content: "What's your name?".into(),
     ///                 name: None,
     ///             },
     ///         ],
@@ -120,45 +280,50 @@ pub trait Stream: Post + Serialize + Sync + Send {
     + Send
     + Sync {
         async move {
-            if !self.is_streaming() {
-                return Err(OapiError::StreamingViolation);
-            }
-
-            let client = reqwest::Client::new();
-
-            let response = client
-                .post(url)
-                .headers({
-                    let mut headers = reqwest::header::HeaderMap::new();
-                    headers.insert("Content-Type", "application/json".parse().unwrap());
-                    headers.insert("Accept", "text/event-stream".parse().unwrap());
-                    headers
-                })
-                .bearer_auth(api_key)
-                .json(self)
-                .send()
-                .await
-                .map_err(|e| OapiError::ResponseError(format!("Failed to send request: {}", e)))?;
-
-            if !response.status().is_success() {
-                return Err(OapiError::ResponseStatus(response.status().as_u16()).into());
-            }
+            let response =
+                send_stream_request(http_client(), self, url, api_key, &HeaderMap::new()).await?;
 
             // The following code is generated by Qwen3-480B-Coder
             // 使用 eventsource-stream 解析 SSE
             let stream = response
                 .bytes_stream()
                 .eventsource()
-                .map(|event| match event {
-                    Ok(event) => Ok(event.data),
-                    Err(e) => Err(OapiError::SseParseError(format!("SSE parse error: {}", e))),
+                .filter_map(|event| {
+                    std::future::ready(match event {
+                        Ok(event) => match event_data(&event.data) {
+                            "" => None,
+                            data => Some(Ok(data.to_string())),
+                        },
+                        Err(e) => Some(Err(OapiError::SseParseError(format!(
+                            "SSE parse error: {}",
+                            e
+                        )))),
+                    })
                 })
+                .take_while(|data| std::future::ready(!matches!(data, Ok(data) if data == DONE)))
                 .boxed();
 
             Ok(stream as BoxStream<'static, Result<String, OapiError>>)
         }
     }
 
+    /// Sends a streaming POST request and returns the parsed responses as a
+    /// [`ResponseStream`].
+    ///
+    /// Unlike [`Stream::get_stream_response`], the returned stream is a concrete type,
+    /// so it can be stored in a struct field without boxing.
+    fn get_stream_response_unboxed(
+        &self,
+        url: &str,
+        api_key: &str,
+    ) -> impl Future<Output = Result<ResponseStream<Self::Response>, OapiError>> + Send + Sync {
+        async move {
+            let response =
+                send_stream_request(http_client(), self, url, api_key, &HeaderMap::new()).await?;
+            Ok(ResponseStream::new(response.into()))
+        }
+    }
+
     fn get_stream_response(
         &self,
         url: &str,
@@ -168,19 +333,685 @@ pub trait Stream: Post + Serialize + Sync + Send {
     > + Send
     + Sync {
         async move {
-            let stream = self.get_stream_response_string(url, api_key).await?;
+            let stream = self.get_stream_response_unboxed(url, api_key).await?;
+            Ok(Box::pin(stream) as BoxStream<'static, _>)
+        }
+    }
 
-            let parsed_stream = stream
-                .take_while(|result| {
-                    let should_continue = match result {
-                        Ok(data) => data != "[DONE]",
-                        Err(_) => true, // 继续传播错误
-                    };
-                    async move { should_continue }
-                })
-                .and_then(|data| async move { Self::Response::from_str(&data) });
+    /// Like [`Stream::get_stream_response`], but sent with `client` instead of
+    /// the client shared by the crate, see [`NoStream::get_response_with_client`].
+    fn get_stream_response_with_client(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        api_key: &str,
+    ) -> impl Future<
+        Output = Result<BoxStream<'static, Result<Self::Response, OapiError>>, OapiError>,
+    > + Send
+    + Sync {
+        async move {
+            let response =
+                send_stream_request(client, self, url, api_key, &HeaderMap::new()).await?;
+            let stream = ResponseStream::<Self::Response>::new(response.into());
+            Ok(Box::pin(stream) as BoxStream<'static, _>)
+        }
+    }
+
+    /// Like [`Stream::get_stream_response`], with `headers` merged onto the
+    /// default headers, see [`request_headers`].
+    fn get_stream_response_with_headers(
+        &self,
+        url: &str,
+        api_key: &str,
+        headers: &HeaderMap,
+    ) -> impl Future<
+        Output = Result<BoxStream<'static, Result<Self::Response, OapiError>>, OapiError>,
+    > + Send
+    + Sync {
+        async move {
+            let response = send_stream_request(http_client(), self, url, api_key, headers).await?;
+            let stream = ResponseStream::<Self::Response>::new(response.into());
+            Ok(Box::pin(stream) as BoxStream<'static, _>)
+        }
+    }
+
+    /// Like [`Stream::get_stream_response`], but blocks the current thread until
+    /// the response starts and returns the events as a
+    /// [`BlockingStream`](super::blocking::BlockingStream) iterator. Needs the
+    /// `blocking` feature.
+    ///
+    /// # Panics
+    ///
+    /// When called from inside an async runtime.
+    #[cfg(feature = "blocking")]
+    fn get_stream_response_blocking(
+        &self,
+        url: &str,
+        api_key: &str,
+    ) -> Result<super::blocking::BlockingStream<Self::Response>, OapiError> {
+        let stream = super::blocking::block_on(self.get_stream_response_unboxed(url, api_key))?;
+        Ok(super::blocking::BlockingStream::new(stream))
+    }
+
+    /// Sends a streaming request and returns every event as untyped JSON,
+    /// ending at the `[DONE]` sentinel.
+    ///
+    /// The streaming counterpart of [`NoStream::get_response_value`].
+    fn get_stream_response_value(
+        &self,
+        url: &str,
+        api_key: &str,
+    ) -> impl Future<
+        Output = Result<BoxStream<'static, Result<serde_json::Value, OapiError>>, OapiError>,
+    > + Send
+    + Sync {
+        async move {
+            let stream = self
+                .get_stream_response_string(url, api_key)
+                .await?
+                .map(|data| data.and_then(|data| Ok(serde_json::from_str(&data)?)))
+                .boxed();
+            Ok(stream)
+        }
+    }
+}
+
+/// Sends requests that are answered with a binary body instead of JSON, such
+/// as the generated audio of the speech endpoint.
+///
+/// The request is posted as JSON like a [`NoStream`] request, and the body of
+/// the response is returned as it is.
+pub trait Binary: Post + Serialize + Sync + Send {
+    /// Sends the request and returns the response once its status is checked.
+    fn get_raw_response(
+        &self,
+        url: &str,
+        key: &str,
+    ) -> impl Future<Output = Result<reqwest::Response, OapiError>> + Send + Sync {
+        async move {
+            if self.is_streaming() {
+                return Err(OapiError::NonStreamingViolation);
+            }
+            send_checked(
+                http_client()
+                    .post(url)
+                    .headers(request_headers("*/*", key, &HeaderMap::new())?)
+                    .json(self),
+            )
+            .await
+        }
+    }
+
+    /// Reads the whole body into memory.
+    fn get_bytes(
+        &self,
+        url: &str,
+        key: &str,
+    ) -> impl Future<Output = Result<Vec<u8>, OapiError>> + Send + Sync {
+        async move {
+            let response = self.get_raw_response(url, key).await?;
+            Ok(response.bytes().await?.to_vec())
+        }
+    }
+
+    /// Streams the body to `writer` as it arrives, and returns the number of
+    /// bytes written. The writer is flushed at the end.
+    fn stream_to_writer<W>(
+        &self,
+        url: &str,
+        key: &str,
+        writer: &mut W,
+    ) -> impl Future<Output = Result<u64, OapiError>> + Send
+    where
+        W: AsyncWrite + Unpin + Send + ?Sized,
+    {
+        async move { write_body(self.get_raw_response(url, key).await?, writer).await }
+    }
+}
+
+/// Sends a request and checks the response status, turning an error status
+/// into an [`OapiError::ApiError`].
+pub(crate) async fn send_checked(
+    request: reqwest::RequestBuilder,
+) -> Result<reqwest::Response, OapiError> {
+    let response = request.send().await?;
+    if !response.status().is_success() {
+        return Err(ApiError::from_response(response).await.into());
+    }
+    Ok(response)
+}
+
+/// Streams the body of a checked response to `writer` chunk by chunk, and
+/// returns the number of bytes written. The writer is flushed at the end.
+pub(crate) async fn write_body<W>(
+    response: reqwest::Response,
+    writer: &mut W,
+) -> Result<u64, OapiError>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    let mut body = response.bytes_stream();
+    let mut written = 0u64;
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk?;
+        writer
+            .write_all(&chunk)
+            .await
+            .map_err(OapiError::FileWriteError)?;
+        written += chunk.len() as u64;
+    }
+    writer.flush().await.map_err(OapiError::FileWriteError)?;
+    Ok(written)
+}
+
+/// Reads the body of a checked response and parses it.
+pub(crate) async fn parse_response<T: FromStr<Err = OapiError>>(
+    response: reqwest::Response,
+) -> Result<T, OapiError> {
+    T::from_str(&response_text(response).await?)
+}
+
+async fn response_text(response: reqwest::Response) -> Result<String, OapiError> {
+    Ok(response.text().await?)
+}
+
+/// Builds the headers of a JSON request: `Content-Type`, `Accept`, and the
+/// bearer `Authorization` of `key`, with `extra` merged on top.
+///
+/// Extra headers replace the defaults of the same name, e.g. to add `X-Title`
+/// and `HTTP-Referer` for OpenRouter or to change `Accept`. An `Authorization`
+/// in `extra` is ignored while `key` is set, so a shared header map cannot
+/// silently replace the key of a request. To send your own `Authorization`
+/// instead, pass an empty `key`.
+///
+/// Fails with [`OapiError::InvalidParameter`] when `accept` or `key` cannot be
+/// sent in a header, e.g. because of a line break.
+pub fn request_headers(accept: &str, key: &str, extra: &HeaderMap) -> Result<HeaderMap, OapiError> {
+    let mut headers = HeaderMap::new();
+    headers.insert("Content-Type", "application/json".parse().unwrap());
+    let accept = HeaderValue::from_str(accept).map_err(|_| {
+        OapiError::InvalidParameter(format!("invalid Accept header value `{}`", accept))
+    })?;
+    headers.insert("Accept", accept);
+
+    for name in extra.keys() {
+        if name == AUTHORIZATION && !key.is_empty() {
+            continue;
+        }
+        headers.remove(name);
+        for value in extra.get_all(name) {
+            headers.append(name.clone(), value.clone());
+        }
+    }
+
+    if !headers.contains_key(AUTHORIZATION) {
+        // The key is not echoed, it is a secret.
+        let mut value = HeaderValue::from_str(&format!("Bearer {}", key)).map_err(|_| {
+            OapiError::InvalidParameter(
+                "the API key contains characters not allowed in a header".to_string(),
+            )
+        })?;
+        value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, value);
+    }
+    Ok(headers)
+}
+
+/// The headers of a multipart request, like [`request_headers`] but leaving
+/// `Content-Type` to the multipart form, which adds its boundary.
+pub(crate) fn multipart_headers(key: &str, extra: &HeaderMap) -> Result<HeaderMap, OapiError> {
+    let mut headers = request_headers("application/json", key, extra)?;
+    headers.remove(reqwest::header::CONTENT_TYPE);
+    Ok(headers)
+}
+
+/// Posts a streaming request with `client` and checks the response status.
+pub(crate) async fn send_stream_request<R: Post + Serialize + Sync + ?Sized>(
+    client: &reqwest::Client,
+    request: &R,
+    url: &str,
+    api_key: &str,
+    headers: &HeaderMap,
+) -> Result<reqwest::Response, OapiError> {
+    if !request.is_streaming() {
+        return Err(OapiError::StreamingViolation);
+    }
+
+    let response = client
+        .post(url)
+        .headers(request_headers("text/event-stream", api_key, headers)?)
+        .json(request)
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(ApiError::from_response(response).await.into());
+    }
+
+    Ok(response)
+}
+
+/// A stream of parsed SSE responses, ending at the `[DONE]` sentinel. Comments
+/// and empty keep-alive events are skipped.
+///
+/// This is the concrete type behind [`Stream::get_stream_response`]. It reads the
+/// response body and parses the SSE events directly, without boxing, so it can be
+/// named and stored in a struct field.
+///
+/// Dropping the stream, or the boxed stream wrapping it, closes the connection
+/// at once, so the provider stops generating, and billing, the rest of the
+/// completion. This is all a "stop generating" button needs to do.
+pub struct ResponseStream<T> {
+    events: EventStream<BodyDataStream<reqwest::Body>>,
+    done: bool,
+    received_done: bool,
+    _response: PhantomData<fn() -> T>,
+}
+
+impl<T> ResponseStream<T> {
+    pub(crate) fn new(body: reqwest::Body) -> Self {
+        Self {
+            events: BodyDataStream::new(body).eventsource(),
+            done: false,
+            received_done: false,
+            _response: PhantomData,
+        }
+    }
+
+    /// Whether the stream ended with the `[DONE]` sentinel. `false` while the
+    /// stream is running, and after the connection was closed without it.
+    pub fn received_done(&self) -> bool {
+        self.received_done
+    }
+}
+
+impl<T: FromStr<Err = OapiError>> futures_util::Stream for ResponseStream<T> {
+    type Item = Result<T, OapiError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            return match this.events.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(event))) => match event_data(&event.data) {
+                    "" => continue,
+                    DONE => {
+                        this.done = true;
+                        this.received_done = true;
+                        Poll::Ready(None)
+                    }
+                    data => Poll::Ready(Some(T::from_str(data))),
+                },
+                Poll::Ready(Some(Err(e))) => Poll::Ready(Some(Err(OapiError::SseParseError(
+                    format!("SSE parse error: {}", e),
+                )))),
+                Poll::Ready(None) => {
+                    this.done = true;
+                    Poll::Ready(None)
+                }
+                Poll::Pending => Poll::Pending,
+            };
+        }
+    }
+}
+
+/// The sentinel data of the last event of a stream.
+const DONE: &str = "[DONE]";
+
+/// The data of an event, trimmed, and without a `data:` prefix repeated by
+/// providers that prefix data that is already framed, e.g.
+/// `data: data: [DONE]`. The `data:` field name itself, with or without a
+/// space, is removed by the SSE parser.
+///
+/// Empty data means the event only holds the connection open. Comment lines,
+/// such as `: keep-alive` or `: OPENROUTER PROCESSING`, never become events,
+/// but some providers ping with an empty `data:` field instead, which would
+/// fail to parse as a response.
+fn event_data(data: &str) -> &str {
+    let data = data.trim();
+    match data.strip_prefix("data:") {
+        Some(data) => data.trim(),
+        None => data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures_util::StreamExt;
+
+    use super::*;
+    use crate::chat::request::{Message, RequestBody};
+    use crate::chat::response::streaming::{
+        ChatCompletionChunk, ChatCompletionStream, StreamAccumulator,
+    };
+    use crate::test_support::{MockResponse, MockServer};
+
+    #[tokio::test]
+    async fn response_stream_stops_at_done() {
+        let body = concat!(
+            "data: {\"id\": \"1\", \"choices\": [{\"index\": 0, \"delta\": {\"content\": \"Hi\", \"role\": \"assistant\"}, \"finish_reason\": null, \"logprobs\": null}], \"created\": 1718345013, \"model\": \"deepseek-chat\", \"object\": \"chat.completion.chunk\"}\n\n",
+            "data: {\"id\": \"1\", \"choices\": [{\"index\": 0, \"delta\": {\"content\": \"\"}, \"finish_reason\": \"stop\", \"logprobs\": null}], \"created\": 1718345013, \"model\": \"deepseek-chat\", \"object\": \"chat.completion.chunk\"}\n\n",
+            "data: [DONE]\n\n",
+        );
+
+        let stream: ChatCompletionStream = ResponseStream::new(reqwest::Body::from(body));
+        let chunks: Vec<ChatCompletionChunk> =
+            stream.map(|chunk| chunk.unwrap()).collect::<Vec<_>>().await;
+
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[1].choices[0].finish_reason.is_some());
+    }
+
+    #[tokio::test]
+    async fn skip_keep_alive_events() {
+        let chunk = r#"{"id": "1", "choices": [{"index": 0, "delta": {"content": "Hi", "role": "assistant"}, "finish_reason": null, "logprobs": null}], "created": 1718345013, "model": "deepseek-chat", "object": "chat.completion.chunk"}"#;
+        let body = format!(
+            ": keep-alive\n\ndata:\n\ndata: {}\n\n: OPENROUTER PROCESSING\n\ndata: \n\ndata: [DONE]\n\n",
+            chunk
+        );
+
+        let stream: ChatCompletionStream = ResponseStream::new(reqwest::Body::from(body.clone()));
+        let chunks: Vec<_> = stream.collect().await;
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].is_ok());
+
+        let server = MockServer::start(vec![MockResponse::new(200, body)]).await;
+        let data: Vec<_> = request(true)
+            .get_stream_response_string(&server.url("/chat/completions"), "key")
+            .await
+            .unwrap()
+            .map(|data| data.unwrap())
+            .collect()
+            .await;
+        assert_eq!(data, vec![chunk.to_string()]);
+    }
+
+    #[tokio::test]
+    async fn done_variants() {
+        let chunk = r#"{"id": "1", "choices": [{"index": 0, "delta": {"content": "Hi", "role": "assistant"}, "finish_reason": null, "logprobs": null}], "created": 1718345013, "model": "deepseek-chat", "object": "chat.completion.chunk"}"#;
+        for done in [
+            "data: [DONE]\n\n",
+            "data:[DONE]\n\n",
+            "data: [DONE] \r\n\r\n",
+            "data: data: [DONE]\n\n",
+            "data:data:[DONE]\n\n",
+        ] {
+            // Nothing after the sentinel is read.
+            let body = format!("data: {}\n\n{}data: not json\n\n", chunk, done);
+            let mut stream: ChatCompletionStream = ResponseStream::new(reqwest::Body::from(body));
+            assert!(stream.next().await.unwrap().is_ok(), "{:?}", done);
+            assert!(stream.next().await.is_none(), "{:?}", done);
+            assert!(stream.received_done(), "{:?}", done);
+        }
+
+        let body = format!("data: data: {}\n\ndata: [DONE]\n\n", chunk);
+        let stream: ChatCompletionStream = ResponseStream::new(reqwest::Body::from(body));
+        let chunks: Vec<_> = stream.collect().await;
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].is_ok());
+    }
+
+    #[tokio::test]
+    async fn accumulator_completeness() {
+        let first = "data: {\"id\": \"1\", \"choices\": [{\"index\": 0, \"delta\": {\"content\": \"Hi\", \"role\": \"assistant\"}, \"finish_reason\": null, \"logprobs\": null}], \"created\": 1718345013, \"model\": \"deepseek-chat\", \"object\": \"chat.completion.chunk\"}\n\n";
+        let last = "data: {\"id\": \"1\", \"choices\": [{\"index\": 0, \"delta\": {\"content\": \"!\"}, \"finish_reason\": \"stop\", \"logprobs\": null}], \"created\": 1718345013, \"model\": \"deepseek-chat\", \"object\": \"chat.completion.chunk\"}\n\n";
+
+        async fn accumulate(body: String) -> StreamAccumulator {
+            let mut stream: ChatCompletionStream = ResponseStream::new(reqwest::Body::from(body));
+            let mut accumulator = StreamAccumulator::new();
+            while let Some(chunk) = stream.next().await {
+                accumulator.push(&chunk.unwrap());
+            }
+            if stream.received_done() {
+                accumulator.push_done();
+            }
+            accumulator
+        }
+
+        // A finish reason, then [DONE].
+        let accumulator = accumulate(format!("{}{}data: [DONE]\n\n", first, last)).await;
+        assert!(accumulator.is_complete());
+        assert_eq!(
+            accumulator.finish().unwrap().choices[0]
+                .message
+                .content
+                .as_deref(),
+            Some("Hi!")
+        );
+
+        // [DONE] alone is enough.
+        assert!(
+            accumulate(format!("{}data: [DONE]\n\n", first))
+                .await
+                .is_complete()
+        );
+
+        // The connection closed in the middle of the answer.
+        let accumulator = accumulate(first.to_string()).await;
+        assert!(!accumulator.is_complete());
+        assert!(accumulator.finish().is_err());
+    }
+
+    fn request(stream: bool) -> RequestBody {
+        RequestBody {
+            messages: vec![Message::User {
+                content: "Hi".into(),
+                name: None,
+            }],
+            model: "deepseek-chat".to_string(),
+            stream,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn response_value() {
+        let server = MockServer::start(vec![MockResponse::json(
+            200,
+            r#"{"id":"1","object":"chat.completion","created":1,"model":"deepseek-chat","choices":[],"new_field":{"nested":true}}"#,
+        )])
+        .await;
+
+        let value = request(false)
+            .get_response_value(&server.url("/chat/completions"), "key")
+            .await
+            .unwrap();
+        assert!(value.is_object());
+        assert_eq!(value["new_field"]["nested"], true);
+    }
+
+    #[tokio::test]
+    async fn error_body() {
+        let server = MockServer::start(vec![MockResponse::json(
+            400,
+            r#"{"error":{"message":"Invalid max_tokens value, the valid range of max_tokens is [1, 8192]","type":"invalid_request_error","param":null,"code":"invalid_request_error"}}"#,
+        )])
+        .await;
+        let url = server.url("/chat/completions");
+
+        for result in [
+            request(false).get_response(&url, "key").await.err(),
+            request(true).get_stream_response(&url, "key").await.err(),
+        ] {
+            match result {
+                Some(OapiError::ApiError(error)) => {
+                    assert_eq!(error.status, 400);
+                    assert!(error.message.starts_with("Invalid max_tokens"));
+                    assert_eq!(error.error_type.as_deref(), Some("invalid_request_error"));
+                }
+                other => panic!("expected an API error, got {:?}", other),
+            }
+        }
+    }
 
-            Ok(Box::pin(parsed_stream) as BoxStream<'static, _>)
+    #[tokio::test]
+    async fn json_string_is_sent_body() {
+        let server = MockServer::start(vec![MockResponse::json(
+            200,
+            r#"{"id":"1","object":"chat.completion","created":1,"model":"deepseek-chat","choices":[]}"#,
+        )])
+        .await;
+        let request = RequestBody {
+            temperature: Some(0.7),
+            ..request(false)
         }
+        .extra("enable_thinking", false);
+
+        request
+            .get_response(&server.url("/chat/completions"), "key")
+            .await
+            .unwrap();
+        assert_eq!(
+            request.to_json_string().unwrap().into_bytes(),
+            server.requests()[0].body
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_header_values() {
+        assert!(matches!(
+            request_headers("text/\nplain", "key", &HeaderMap::new()),
+            Err(OapiError::InvalidParameter(_))
+        ));
+
+        let server = MockServer::start(vec![MockResponse::json(200, "{}")]).await;
+        let url = server.url("/chat/completions");
+        assert!(matches!(
+            request(false).get_response(&url, "sk-\n").await,
+            Err(OapiError::InvalidParameter(e)) if !e.contains("sk-")
+        ));
+        assert!(matches!(
+            request(true).get_stream_response(&url, "sk-\n").await,
+            Err(OapiError::InvalidParameter(_))
+        ));
+        // Nothing is sent without the key.
+        assert!(server.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn accept_any_success_status() {
+        let server = MockServer::start(vec![
+            MockResponse::json(
+                201,
+                r#"{"id":"1","object":"chat.completion","created":1,"model":"deepseek-chat","choices":[]}"#,
+            ),
+            MockResponse::json(400, r#"{"error":{"message":"bad request"}}"#),
+        ])
+        .await;
+        let url = server.url("/chat/completions");
+
+        assert!(request(false).get_response(&url, "key").await.is_ok());
+        assert_eq!(
+            request(false)
+                .get_response(&url, "key")
+                .await
+                .unwrap_err()
+                .status(),
+            Some(400)
+        );
+    }
+
+    #[tokio::test]
+    async fn response_with_rate_limits() {
+        let server = MockServer::start(vec![
+            MockResponse::json(
+                200,
+                r#"{"id":"1","object":"chat.completion","created":1,"model":"deepseek-chat","choices":[{"index":0,"message":{"role":"assistant","content":"Hi"},"finish_reason":"stop"}]}"#,
+            )
+            .with_header("x-ratelimit-limit-requests", "10000")
+            .with_header("x-ratelimit-remaining-requests", "9999")
+            .with_header("x-ratelimit-remaining-tokens", "149984")
+            .with_header("x-ratelimit-reset-requests", "6m0s")
+            .with_header("x-ratelimit-reset-tokens", "20ms"),
+        ])
+        .await;
+
+        let (completion, info) = request(false)
+            .get_response_with_meta(&server.url("/chat/completions"), "key")
+            .await
+            .unwrap();
+        assert_eq!(completion.choices[0].message.content.as_deref(), Some("Hi"));
+        assert_eq!(
+            info,
+            RateLimitInfo {
+                limit_requests: Some(10000),
+                limit_tokens: None,
+                remaining_requests: Some(9999),
+                remaining_tokens: Some(149984),
+                reset_requests: Some(Duration::from_secs(360)),
+                reset_tokens: Some(Duration::from_millis(20)),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_response_value() {
+        let server = MockServer::start(vec![MockResponse::sse([
+            r#"{"id":"1","object":"chat.completion.chunk","choices":[]}"#,
+            r#"{"id":"1","object":"chat.completion.chunk","choices":[],"usage":{}}"#,
+            "[DONE]",
+        ])])
+        .await;
+
+        let values: Vec<serde_json::Value> = request(true)
+            .get_stream_response_value(&server.url("/chat/completions"), "key")
+            .await
+            .unwrap()
+            .map(|value| value.unwrap())
+            .collect()
+            .await;
+        assert_eq!(values.len(), 2);
+        assert!(values.iter().all(|value| value.is_object()));
+        assert!(values[1].get("usage").is_some());
+    }
+
+    #[tokio::test]
+    async fn dropping_stream_closes_connection() {
+        let chunk = r#"{"id":"1","object":"chat.completion.chunk","created":1,"model":"deepseek-chat","choices":[{"index":0,"delta":{"content":"token "},"finish_reason":null}]}"#;
+        let server = MockServer::start(vec![
+            MockResponse::sse(std::iter::repeat_n(chunk, 500))
+                .with_part_delay(Duration::from_millis(10)),
+        ])
+        .await;
+
+        let mut stream = request(true)
+            .get_stream_response(&server.url("/chat/completions"), "key")
+            .await
+            .unwrap();
+        for _ in 0..3 {
+            stream.next().await.unwrap().unwrap();
+        }
+        drop(stream);
+
+        // Sending the whole response would take 5 seconds.
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+        while server.disconnects() == 0 && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(server.disconnects(), 1);
+    }
+
+    #[tokio::test]
+    async fn stream_response_string_ends_at_done() {
+        let server = MockServer::start(vec![MockResponse::sse([
+            r#"{"id":"1"}"#,
+            "[DONE]",
+            r#"{"id":"2"}"#,
+        ])])
+        .await;
+
+        let data: Vec<String> = request(true)
+            .get_stream_response_string(&server.url("/chat/completions"), "key")
+            .await
+            .unwrap()
+            .map(|data| data.unwrap())
+            .collect()
+            .await;
+        assert_eq!(data, vec![r#"{"id":"1"}"#]);
     }
 }
@@ -0,0 +1,351 @@
+//! Client-side rate limiting.
+//!
+//! [`RateLimiter`] is a token bucket over requests per minute and tokens per
+//! minute. The typed [`Client`](super::client::Client) awaits it before sending
+//! every request, so a burst of calls is spread out instead of tripping the
+//! provider's limits.
+//!
+//! [`RateLimitInfo`] reads the limits the provider reports back in the
+//! `x-ratelimit-*` headers of a response, see
+//! [`NoStream::get_response_with_meta`](super::post::NoStream::get_response_with_meta).
+//! The `Client` feeds them back into its limiter with [`RateLimiter::update`],
+//! so it slows down when the provider's budget runs out before the local one.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use reqwest::header::HeaderMap;
+
+/// Limits enforced by a [`RateLimiter`]. `None` leaves that dimension unlimited.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RateLimits {
+    /// Maximum number of requests per minute. Requests are spread evenly over
+    /// the minute instead of being sent in a burst.
+    pub requests_per_minute: Option<u32>,
+    /// Maximum number of tokens per minute. Up to one minute's worth of tokens
+    /// can be spent at once.
+    pub tokens_per_minute: Option<u32>,
+}
+
+/// The rate limits of the provider, from the `x-ratelimit-*` headers of a
+/// response. Headers that are missing or cannot be parsed are `None`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RateLimitInfo {
+    /// `x-ratelimit-limit-requests`: the requests allowed before the limit is
+    /// reached.
+    pub limit_requests: Option<u64>,
+    /// `x-ratelimit-limit-tokens`: the tokens allowed before the limit is
+    /// reached.
+    pub limit_tokens: Option<u64>,
+    /// `x-ratelimit-remaining-requests`: the requests left before the limit is
+    /// reached.
+    pub remaining_requests: Option<u64>,
+    /// `x-ratelimit-remaining-tokens`: the tokens left before the limit is
+    /// reached.
+    pub remaining_tokens: Option<u64>,
+    /// `x-ratelimit-reset-requests`: the time until the request limit is reset.
+    pub reset_requests: Option<Duration>,
+    /// `x-ratelimit-reset-tokens`: the time until the token limit is reset.
+    pub reset_tokens: Option<Duration>,
+}
+
+impl RateLimitInfo {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+        };
+        let count = |name: &str| header(name).and_then(|value| value.parse().ok());
+        let reset = |name: &str| header(name).and_then(parse_reset);
+
+        Self {
+            limit_requests: count("x-ratelimit-limit-requests"),
+            limit_tokens: count("x-ratelimit-limit-tokens"),
+            remaining_requests: count("x-ratelimit-remaining-requests"),
+            remaining_tokens: count("x-ratelimit-remaining-tokens"),
+            reset_requests: reset("x-ratelimit-reset-requests"),
+            reset_tokens: reset("x-ratelimit-reset-tokens"),
+        }
+    }
+}
+
+/// Parses a reset time such as `1s`, `6m0s`, `20ms` or `1h2m3.5s`. A bare
+/// number is read as seconds, as some gateways send it.
+fn parse_reset(value: &str) -> Option<Duration> {
+    if value.is_empty() {
+        return None;
+    }
+    if let Ok(seconds) = value.parse::<f64>() {
+        return (seconds.is_finite() && seconds >= 0.0).then(|| Duration::from_secs_f64(seconds));
+    }
+
+    let mut total = 0.0;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .filter(|&end| end > 0)?;
+        let number: f64 = rest[..number_end].parse().ok()?;
+        rest = &rest[number_end..];
+        let unit_end = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let seconds_per_unit = match &rest[..unit_end] {
+            "h" => 3600.0,
+            "m" => 60.0,
+            "s" => 1.0,
+            "ms" => 0.001,
+            _ => return None,
+        };
+        total += number * seconds_per_unit;
+        rest = &rest[unit_end..];
+    }
+    Some(Duration::from_secs_f64(total))
+}
+
+/// A token bucket limiter that can be shared between tasks.
+#[derive(Debug)]
+pub struct RateLimiter {
+    buckets: Mutex<Buckets>,
+}
+
+#[derive(Debug)]
+struct Buckets {
+    requests: Option<Bucket>,
+    tokens: Option<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    available: f64,
+    refill_per_second: f64,
+    last_refill: Instant,
+    /// Set when the provider reported the budget as exhausted: nothing refills
+    /// before this instant, and the bucket is full again after it.
+    resume_at: Option<Instant>,
+}
+
+impl Bucket {
+    fn new(per_minute: u32, capacity: f64, now: Instant) -> Self {
+        Self {
+            capacity,
+            available: capacity,
+            refill_per_second: per_minute as f64 / 60.0,
+            last_refill: now,
+            resume_at: None,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        if let Some(resume_at) = self.resume_at {
+            if now < resume_at {
+                self.last_refill = now;
+                return;
+            }
+            self.available = self.capacity;
+            self.resume_at = None;
+        }
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.available = (self.available + elapsed * self.refill_per_second).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// How long to wait until `amount` is available. A request larger than the
+    /// bucket only waits for a full bucket, otherwise it could never be sent.
+    fn wait_time(&self, amount: f64, now: Instant) -> Duration {
+        if let Some(resume_at) = self.resume_at {
+            return resume_at.saturating_duration_since(now);
+        }
+        let missing = amount.min(self.capacity) - self.available;
+        if missing <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(missing / self.refill_per_second)
+        }
+    }
+
+    /// Lowers the available budget to the `remaining` one reported by the
+    /// provider. An exhausted budget pauses the bucket until `reset`.
+    fn update(&mut self, remaining: Option<u64>, reset: Option<Duration>, now: Instant) {
+        let Some(remaining) = remaining else {
+            return;
+        };
+        self.refill(now);
+        self.available = self.available.min(remaining as f64);
+        if remaining == 0
+            && let Some(reset) = reset
+        {
+            self.resume_at = Some(now + reset);
+        }
+    }
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        let now = Instant::now();
+        Self {
+            buckets: Mutex::new(Buckets {
+                requests: limits
+                    .requests_per_minute
+                    .filter(|&rpm| rpm > 0)
+                    .map(|rpm| Bucket::new(rpm, 1.0, now)),
+                tokens: limits
+                    .tokens_per_minute
+                    .filter(|&tpm| tpm > 0)
+                    .map(|tpm| Bucket::new(tpm, tpm as f64, now)),
+            }),
+        }
+    }
+
+    /// Waits until one request spending `tokens` tokens is allowed, then
+    /// records it.
+    pub async fn acquire(&self, tokens: u32) {
+        loop {
+            let wait = {
+                let mut buckets = self.buckets.lock().unwrap();
+                let now = Instant::now();
+                let tokens = tokens as f64;
+
+                let mut wait = Duration::ZERO;
+                if let Some(bucket) = buckets.requests.as_mut() {
+                    bucket.refill(now);
+                    wait = wait.max(bucket.wait_time(1.0, now));
+                }
+                if let Some(bucket) = buckets.tokens.as_mut() {
+                    bucket.refill(now);
+                    wait = wait.max(bucket.wait_time(tokens, now));
+                }
+
+                if wait.is_zero() {
+                    if let Some(bucket) = buckets.requests.as_mut() {
+                        bucket.available -= 1.0;
+                    }
+                    if let Some(bucket) = buckets.tokens.as_mut() {
+                        bucket.available -= tokens.min(bucket.capacity);
+                    }
+                    return;
+                }
+                wait
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Adjusts the limiter to the limits the provider reported in `info`.
+    ///
+    /// The available budget is lowered to `remaining_requests` and
+    /// `remaining_tokens`, never raised above the local limits. When either is
+    /// exhausted, no request is let through until its `reset_*` time, after
+    /// which the budget is full again. Dimensions this limiter does not limit
+    /// are ignored.
+    pub fn update(&self, info: &RateLimitInfo) {
+        let mut buckets = self.buckets.lock().unwrap();
+        let now = Instant::now();
+        if let Some(bucket) = buckets.requests.as_mut() {
+            bucket.update(info.remaining_requests, info.reset_requests, now);
+        }
+        if let Some(bucket) = buckets.tokens.as_mut() {
+            bucket.update(info.remaining_tokens, info.reset_tokens, now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reset_times() {
+        assert_eq!(parse_reset("1s"), Some(Duration::from_secs(1)));
+        assert_eq!(
+            parse_reset("1h2m3.5s"),
+            Some(Duration::from_secs_f64(3723.5))
+        );
+        assert_eq!(parse_reset("0.25"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_reset("soon"), None);
+        assert_eq!(parse_reset("5d"), None);
+        assert_eq!(parse_reset(""), None);
+    }
+
+    #[tokio::test]
+    async fn requests_are_spaced() {
+        let limiter = RateLimiter::new(RateLimits {
+            requests_per_minute: Some(600),
+            ..Default::default()
+        });
+
+        let start = Instant::now();
+        for _ in 0..4 {
+            limiter.acquire(0).await;
+        }
+        let elapsed = start.elapsed();
+
+        // 600 requests per minute is one request every 100ms; the first one
+        // goes out immediately.
+        assert!(elapsed >= Duration::from_millis(290), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(1000), "{:?}", elapsed);
+    }
+
+    #[tokio::test]
+    async fn tokens_are_limited() {
+        let limiter = RateLimiter::new(RateLimits {
+            tokens_per_minute: Some(6000),
+            ..Default::default()
+        });
+
+        let start = Instant::now();
+        limiter.acquire(6000).await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        // The bucket is empty now, and 6000 tokens per minute refill 20 tokens
+        // in 200ms.
+        limiter.acquire(20).await;
+        assert!(start.elapsed() >= Duration::from_millis(190));
+    }
+
+    #[tokio::test]
+    async fn provider_limits_are_followed() {
+        let limiter = RateLimiter::new(RateLimits {
+            requests_per_minute: Some(60_000),
+            tokens_per_minute: Some(6000),
+        });
+
+        // 100 tokens left; 6000 tokens per minute refill 20 tokens in 200ms.
+        limiter.update(&RateLimitInfo {
+            remaining_tokens: Some(100),
+            ..Default::default()
+        });
+        let start = Instant::now();
+        limiter.acquire(100).await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+        limiter.acquire(20).await;
+        assert!(start.elapsed() >= Duration::from_millis(190));
+
+        // No requests left until the reset, then the full budget.
+        limiter.update(&RateLimitInfo {
+            remaining_requests: Some(0),
+            reset_requests: Some(Duration::from_millis(300)),
+            ..Default::default()
+        });
+        let start = Instant::now();
+        limiter.acquire(0).await;
+        assert!(start.elapsed() >= Duration::from_millis(290));
+        assert!(start.elapsed() < Duration::from_millis(1000));
+    }
+
+    #[tokio::test]
+    async fn unlimited_does_not_wait() {
+        let limiter = RateLimiter::new(RateLimits::default());
+
+        let start = Instant::now();
+        for _ in 0..100 {
+            limiter.acquire(100_000).await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+}
@@ -0,0 +1,269 @@
+//! Recording and replaying HTTP interactions, for tests that must not depend on
+//! a live provider. Enabled by the `replay` feature.
+//!
+//! A [`Cassette`] sits in front of the [`NoStream`] trait methods. The first
+//! run sends each request for real and writes the response body into a JSON
+//! file; later runs find the request by its [`cache_key`] and return the
+//! recorded body without touching the network. Commit the cassette next to the
+//! tests that use it.
+//!
+//! The API key is neither recorded nor part of the key, so a cassette recorded
+//! with a real key replays with any placeholder. Streaming requests are not
+//! recorded.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::post::NoStream;
+use crate::errors::OapiError;
+
+/// Whether a [`Cassette`] sends requests or answers them from the file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayMode {
+    /// Replay recorded interactions and record the missing ones.
+    #[default]
+    Auto,
+    /// Always send requests, overwriting matching interactions.
+    Record,
+    /// Only replay. A request missing from the cassette is an error.
+    Replay,
+}
+
+/// One recorded request and the body of its successful response.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Interaction {
+    pub url: String,
+    pub request: serde_json::Value,
+    pub response: String,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: BTreeMap<String, Interaction>,
+}
+
+/// A JSON file of recorded interactions, keyed by [`cache_key`].
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: ReplayMode,
+    interactions: Mutex<BTreeMap<String, Interaction>>,
+}
+
+impl Cassette {
+    /// Opens the cassette at `path`. A missing file is an empty cassette, and
+    /// is created once the first interaction is recorded.
+    pub fn open(path: impl AsRef<Path>, mode: ReplayMode) -> Result<Self, OapiError> {
+        let path = path.as_ref().to_path_buf();
+        let interactions = match std::fs::read_to_string(&path) {
+            Ok(text) => {
+                serde_json::from_str::<CassetteFile>(&text)
+                    .map_err(|e| {
+                        OapiError::DeserializationError(format!(
+                            "Failed to parse cassette {}: {}",
+                            path.display(),
+                            e
+                        ))
+                    })?
+                    .interactions
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(OapiError::FileReadError(e)),
+        };
+
+        Ok(Self {
+            path,
+            mode,
+            interactions: Mutex::new(interactions),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn mode(&self) -> ReplayMode {
+        self.mode
+    }
+
+    /// Returns the recorded interaction for a request, if any.
+    pub fn interaction<R: Serialize>(
+        &self,
+        url: &str,
+        request: &R,
+    ) -> Result<Option<Interaction>, OapiError> {
+        let key = cache_key(url, request)?;
+        Ok(self.interactions.lock().unwrap().get(&key).cloned())
+    }
+
+    /// Like [`NoStream::get_response_string`], but replays or records the
+    /// response according to the cassette's [`ReplayMode`].
+    pub async fn get_response_string<R: NoStream>(
+        &self,
+        request: &R,
+        url: &str,
+        key: &str,
+    ) -> Result<String, OapiError> {
+        let cache_key = cache_key(url, request)?;
+
+        if self.mode != ReplayMode::Record
+            && let Some(interaction) = self.interactions.lock().unwrap().get(&cache_key)
+        {
+            return Ok(interaction.response.clone());
+        }
+        if self.mode == ReplayMode::Replay {
+            return Err(OapiError::ResponseError(format!(
+                "No interaction recorded for {} in cassette {}",
+                url,
+                self.path.display()
+            )));
+        }
+
+        let response = request.get_response_string(url, key).await?;
+        let interaction = Interaction {
+            url: url.to_string(),
+            request: to_value(request)?,
+            response: response.clone(),
+        };
+        self.interactions
+            .lock()
+            .unwrap()
+            .insert(cache_key, interaction);
+        self.save()?;
+
+        Ok(response)
+    }
+
+    /// Like [`NoStream::get_response`], but replays or records the response
+    /// according to the cassette's [`ReplayMode`].
+    pub async fn get_response<R: NoStream>(
+        &self,
+        request: &R,
+        url: &str,
+        key: &str,
+    ) -> Result<R::Response, OapiError> {
+        let text = self.get_response_string(request, url, key).await?;
+        R::Response::from_str(&text)
+    }
+
+    /// Writes the cassette to its file. Recording does this after every new
+    /// interaction.
+    pub fn save(&self) -> Result<(), OapiError> {
+        let file = CassetteFile {
+            interactions: self.interactions.lock().unwrap().clone(),
+        };
+        let text = serde_json::to_string_pretty(&file).map_err(|e| {
+            OapiError::SerializationError(format!("Failed to serialize cassette: {}", e))
+        })?;
+        std::fs::write(&self.path, text).map_err(OapiError::FileWriteError)
+    }
+}
+
+/// Returns a stable key for a request sent to `url`.
+///
+/// The key is a 64-bit FNV-1a hash of the URL and the request body as JSON
+/// with sorted object keys, so it does not change between runs, platforms or
+/// compiler versions.
+pub fn cache_key<R: Serialize + ?Sized>(url: &str, request: &R) -> Result<String, OapiError> {
+    let body = to_value(request)?.to_string();
+
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in url.bytes().chain([0]).chain(body.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    Ok(format!("{:016x}", hash))
+}
+
+fn to_value<R: Serialize + ?Sized>(request: &R) -> Result<serde_json::Value, OapiError> {
+    serde_json::to_value(request)
+        .map_err(|e| OapiError::SerializationError(format!("Failed to serialize request: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat::request::{Message, RequestBody};
+    use crate::test_support::{MockResponse, MockServer};
+
+    const COMPLETION: &str = r#"{"id":"1","object":"chat.completion","created":1,"model":"deepseek-chat","choices":[{"index":0,"message":{"role":"assistant","content":"Hello!"},"finish_reason":"stop"}]}"#;
+
+    fn request(content: &str) -> RequestBody {
+        RequestBody {
+            messages: vec![Message::User {
+                content: content.into(),
+                name: None,
+            }],
+            model: "deepseek-chat".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn cache_key_is_stable() {
+        let url = "https://api.deepseek.com/chat/completions";
+        assert_eq!(
+            cache_key(url, &request("Hi")).unwrap(),
+            cache_key(url, &request("Hi")).unwrap()
+        );
+        assert_ne!(
+            cache_key(url, &request("Hi")).unwrap(),
+            cache_key(url, &request("Hello")).unwrap()
+        );
+        assert_ne!(
+            cache_key(url, &request("Hi")).unwrap(),
+            cache_key(
+                "https://api.deepseek.com/beta/chat/completions",
+                &request("Hi")
+            )
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn record_then_replay() {
+        let server = MockServer::start(vec![MockResponse::json(200, COMPLETION)]).await;
+        let url = server.url("/chat/completions");
+        let path = std::env::temp_dir().join(format!(
+            "openai-interface-cassette-{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let cassette = Cassette::open(&path, ReplayMode::Auto).unwrap();
+        let recorded = cassette
+            .get_response(&request("Hi"), &url, "key")
+            .await
+            .unwrap();
+        assert_eq!(
+            recorded.choices[0].message.content.as_deref(),
+            Some("Hello!")
+        );
+        assert_eq!(server.requests().len(), 1);
+
+        // A fresh cassette reads the file back and never reaches the server.
+        let cassette = Cassette::open(&path, ReplayMode::Replay).unwrap();
+        let replayed = cassette
+            .get_response(&request("Hi"), &url, "another key")
+            .await
+            .unwrap();
+        assert_eq!(
+            replayed.choices[0].message.content.as_deref(),
+            Some("Hello!")
+        );
+        assert_eq!(server.requests().len(), 1);
+
+        assert!(matches!(
+            cassette.get_response(&request("Bye"), &url, "key").await,
+            Err(OapiError::ResponseError(_))
+        ));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,123 @@
+//! Retrying requests that failed for a transient reason.
+//!
+//! Providers answer `429 Too Many Requests` when a rate limit is hit, and
+//! gateways answer `502`, `503` or `504` when the model servers are overloaded.
+//! These requests were not processed, and neither were requests that failed
+//! to connect, so a [`Client`](super::client::Client) with a [`RetryPolicy`]
+//! sends them again after a backoff. Other errors are returned at once: a
+//! request that timed out or lost its connection may have been processed, and
+//! sending it again could bill it twice.
+
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+use crate::errors::OapiError;
+
+/// When and how often a [`Client`](super::client::Client) sends a failed
+/// request again.
+///
+/// The delay before attempt `n + 1` is `base_delay * 2^(n - 1)`, spread by up
+/// to `jitter` of itself in either direction so that concurrent clients do not
+/// retry in lockstep. A `Retry-After` header of the failed response takes
+/// precedence over the backoff.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// The number of attempts, including the first one. `1` disables retries.
+    pub max_attempts: u32,
+    /// The delay before the second attempt.
+    pub base_delay: Duration,
+    /// The fraction of the delay, between 0 and 1, by which it is randomly
+    /// spread.
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            jitter: 0.2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether `error` is a transient failure of a request that was not
+    /// processed: a `429`, `502`, `503` or `504` status, or a failed
+    /// connection.
+    pub fn is_retryable(error: &OapiError) -> bool {
+        matches!(error, OapiError::Connect(_))
+            || matches!(error.status(), Some(429 | 502 | 503 | 504))
+    }
+
+    /// The delay after the failed attempt number `attempt`, starting at 1.
+    pub fn delay(&self, attempt: u32, error: &OapiError) -> Duration {
+        if let OapiError::ApiError(error) = error
+            && let Some(retry_after) = error.retry_after
+        {
+            return retry_after;
+        }
+
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)));
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        // A factor between 1 - jitter and 1 + jitter.
+        let factor = 1.0 + jitter * (2.0 * random_fraction() - 1.0);
+        backoff.mul_f64(factor)
+    }
+}
+
+/// A random number in `[0, 1)`, from the random keys of the standard library.
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u8(0);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ApiError;
+
+    #[test]
+    fn backoff_and_retry_after() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(100),
+            jitter: 0.0,
+        };
+        let unavailable = OapiError::ApiError(Box::new(ApiError::from_body(503, "")));
+        assert!(RetryPolicy::is_retryable(&unavailable));
+        assert_eq!(policy.delay(1, &unavailable), Duration::from_millis(100));
+        assert_eq!(policy.delay(3, &unavailable), Duration::from_millis(400));
+
+        let rate_limited = OapiError::ApiError(Box::new(ApiError {
+            retry_after: Some(Duration::from_secs(7)),
+            ..ApiError::from_body(429, "")
+        }));
+        assert_eq!(policy.delay(1, &rate_limited), Duration::from_secs(7));
+
+        let jittered = RetryPolicy {
+            jitter: 0.5,
+            ..policy
+        };
+        for _ in 0..100 {
+            let delay = jittered.delay(1, &unavailable);
+            assert!(delay >= Duration::from_millis(50) && delay <= Duration::from_millis(150));
+        }
+
+        assert!(!RetryPolicy::is_retryable(&OapiError::ApiError(Box::new(
+            ApiError::from_body(400, "")
+        ))));
+        assert!(!RetryPolicy::is_retryable(&OapiError::Timeout(
+            String::new()
+        )));
+        assert!(RetryPolicy::is_retryable(
+            &OapiError::Connect(String::new())
+        ));
+    }
+}
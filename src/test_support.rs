@@ -0,0 +1,288 @@
+//! A minimal HTTP/1.1 server for testing the request code paths without a
+//! live provider.
+
+// Not every helper is used by every feature combination.
+#![allow(dead_code)]
+
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+/// A request received by a [`MockServer`].
+#[derive(Debug, Clone)]
+pub(crate) struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    /// Header names are lowercase.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn body_json(&self) -> serde_json::Value {
+        serde_json::from_slice(&self.body).unwrap()
+    }
+}
+
+/// A canned response served by a [`MockServer`].
+#[derive(Debug, Clone)]
+pub(crate) struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    parts: Vec<Vec<u8>>,
+    delay: Duration,
+    part_delay: Duration,
+}
+
+impl MockResponse {
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            parts: vec![body.into()],
+            delay: Duration::ZERO,
+            part_delay: Duration::ZERO,
+        }
+    }
+
+    pub fn json(status: u16, body: &str) -> Self {
+        Self::new(status, body).with_header("Content-Type", "application/json")
+    }
+
+    /// An SSE response sending one `data:` event per part.
+    pub fn sse<'a>(events: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut response =
+            Self::new(200, Vec::new()).with_header("Content-Type", "text/event-stream");
+        response.parts = events
+            .into_iter()
+            .map(|event| format!("data: {}\n\n", event).into_bytes())
+            .collect();
+        response
+    }
+
+    /// A response sending the given raw body parts one after another.
+    pub fn parts<'a>(status: u16, parts: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut response = Self::new(status, Vec::new());
+        response.parts = parts
+            .into_iter()
+            .map(|part| part.as_bytes().to_vec())
+            .collect();
+        response
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Waits before sending the response headers.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Waits before sending each body part after the first.
+    pub fn with_part_delay(mut self, delay: Duration) -> Self {
+        self.part_delay = delay;
+        self
+    }
+}
+
+/// Serves the given responses in order, repeating the last one.
+pub(crate) struct MockServer {
+    address: std::net::SocketAddr,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    disconnects: Arc<AtomicUsize>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl MockServer {
+    pub async fn start(responses: Vec<MockResponse>) -> Self {
+        Self::start_at("127.0.0.1:0", responses).await
+    }
+
+    /// Like [`MockServer::start`], listening on the given address.
+    pub async fn start_at(address: &str, responses: Vec<MockResponse>) -> Self {
+        assert!(!responses.is_empty());
+        let listener = TcpListener::bind(address).await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let disconnects = Arc::new(AtomicUsize::new(0));
+        let recorded = Arc::clone(&requests);
+        let failed = Arc::clone(&disconnects);
+        let task = tokio::spawn(async move {
+            let mut served = 0;
+            loop {
+                let Ok((socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let response = responses[served.min(responses.len() - 1)].clone();
+                served += 1;
+                let recorded = Arc::clone(&recorded);
+                let failed = Arc::clone(&failed);
+                tokio::spawn(async move {
+                    if handle(socket, response, recorded).await.is_err() {
+                        failed.fetch_add(1, Ordering::SeqCst);
+                    }
+                });
+            }
+        });
+
+        Self {
+            address,
+            requests,
+            disconnects,
+            task,
+        }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.address, path)
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// The number of connections the client closed before the whole response
+    /// was sent.
+    pub fn disconnects(&self) -> usize {
+        self.disconnects.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn handle(
+    mut socket: TcpStream,
+    response: MockResponse,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+) -> std::io::Result<()> {
+    let request = read_request(&mut socket).await?;
+    requests.lock().unwrap().push(request);
+
+    tokio::time::sleep(response.delay).await;
+
+    let mut head = format!("HTTP/1.1 {} Mock\r\nConnection: close\r\n", response.status);
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    if response.parts.len() == 1 {
+        head.push_str(&format!("Content-Length: {}\r\n", response.parts[0].len()));
+    }
+    head.push_str("\r\n");
+    socket.write_all(head.as_bytes()).await?;
+
+    for (i, part) in response.parts.iter().enumerate() {
+        if i > 0 {
+            tokio::time::sleep(response.part_delay).await;
+        }
+        socket.write_all(part).await?;
+        socket.flush().await?;
+    }
+    socket.shutdown().await
+}
+
+async fn read_request(socket: &mut TcpStream) -> std::io::Result<RecordedRequest> {
+    let mut buffer = Vec::new();
+    let header_end = loop {
+        if let Some(end) = find(&buffer, b"\r\n\r\n") {
+            break end;
+        }
+        read_more(socket, &mut buffer).await?;
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+
+    let mut body = buffer.split_off(header_end + 4);
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    };
+
+    if let Some(length) = header("content-length") {
+        let length: usize = length.parse().unwrap_or(0);
+        while body.len() < length {
+            read_more(socket, &mut body).await?;
+        }
+    } else if header("transfer-encoding").is_some_and(|value| value.contains("chunked")) {
+        body = read_chunked(socket, body).await?;
+    }
+
+    Ok(RecordedRequest {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+async fn read_chunked(socket: &mut TcpStream, mut raw: Vec<u8>) -> std::io::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let line_end = loop {
+            if let Some(end) = find(&raw, b"\r\n") {
+                break end;
+            }
+            read_more(socket, &mut raw).await?;
+        };
+        let size_line = String::from_utf8_lossy(&raw[..line_end]).to_string();
+        let size =
+            usize::from_str_radix(size_line.split(';').next().unwrap().trim(), 16).unwrap_or(0);
+        while raw.len() < line_end + 2 + size + 2 {
+            read_more(socket, &mut raw).await?;
+        }
+        body.extend_from_slice(&raw[line_end + 2..line_end + 2 + size]);
+        raw.drain(..line_end + 2 + size + 2);
+        if size == 0 {
+            return Ok(body);
+        }
+    }
+}
+
+async fn read_more(socket: &mut TcpStream, buffer: &mut Vec<u8>) -> std::io::Result<()> {
+    let mut chunk = [0; 8192];
+    let read = socket.read(&mut chunk).await?;
+    if read == 0 {
+        return Err(std::io::ErrorKind::UnexpectedEof.into());
+    }
+    buffer.extend_from_slice(&chunk[..read]);
+    Ok(())
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
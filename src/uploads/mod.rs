@@ -0,0 +1,325 @@
+//! Uploading large files in parts.
+//!
+//! A file sent with [`CreateFileRequest`](crate::files::create::request::CreateFileRequest)
+//! goes out in one request, so a dropped connection means starting over. The
+//! Uploads API instead takes the file in parts of up to 64 MB: create an
+//! upload, add the parts, then complete it to get a regular file.
+//!
+//! - [`request`]: The three requests of the Uploads API
+//! - [`response`]: Upload and part objects
+//! - [`ChunkedUpload`]: Splits a file into parts, uploads them, retrying failed
+//!   parts, and completes the upload
+//!
+//! Only providers implementing the Uploads API support this, such as OpenAI.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::path::PathBuf;
+//! use openai_interface::files::create::request::FilePurpose;
+//! use openai_interface::uploads::ChunkedUpload;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let upload = ChunkedUpload {
+//!         file: PathBuf::from("training.jsonl"),
+//!         purpose: FilePurpose::FineTune,
+//!         ..Default::default()
+//!     };
+//!
+//!     let file = upload.upload("https://api.openai.com/v1", "sk-...").await?;
+//!     println!("Uploaded file ID: {}", file.id);
+//!     Ok(())
+//! }
+//! ```
+
+pub mod request;
+pub mod response;
+
+use std::{path::PathBuf, time::Duration};
+
+use tokio::io::AsyncReadExt;
+
+use crate::errors::OapiError;
+use crate::files::create::request::FilePurpose;
+use crate::files::create::response::FileObject;
+use crate::rest::client::join_url;
+use crate::rest::post::NoStream;
+use crate::rest::retry::RetryPolicy;
+use request::{AddUploadPartRequest, CompleteUploadRequest, CreateUploadRequest};
+
+/// The largest part the Uploads API accepts.
+pub const MAX_PART_SIZE: usize = 64 * 1024 * 1024;
+
+/// Uploads a file through the Uploads API.
+#[derive(Debug, Clone)]
+pub struct ChunkedUpload {
+    /// The file to upload.
+    pub file: PathBuf,
+    /// The intended purpose of the uploaded file.
+    pub purpose: FilePurpose,
+    /// The MIME type of the file. Defaults to `text/jsonl`, the type of batch and
+    /// fine-tuning files.
+    pub mime_type: String,
+    /// The size of each part in bytes, at most [`MAX_PART_SIZE`].
+    pub part_size: usize,
+    /// How many times a failed part is sent again before giving up.
+    pub max_retries: u32,
+    /// How long to wait before sending a failed part again.
+    pub retry_delay: Duration,
+}
+
+impl Default for ChunkedUpload {
+    fn default() -> Self {
+        Self {
+            file: PathBuf::new(),
+            purpose: FilePurpose::default(),
+            mime_type: "text/jsonl".to_string(),
+            part_size: MAX_PART_SIZE,
+            max_retries: 3,
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl ChunkedUpload {
+    /// Uploads the file to the provider at `base_url`, e.g.
+    /// `https://api.openai.com/v1`, and returns the completed file.
+    pub async fn upload(&self, base_url: &str, key: &str) -> Result<FileObject, OapiError> {
+        if self.part_size == 0 || self.part_size > MAX_PART_SIZE {
+            return Err(OapiError::InvalidParameter(format!(
+                "part_size must be between 1 and {} bytes, got {}",
+                MAX_PART_SIZE, self.part_size
+            )));
+        }
+        if !self.file.exists() {
+            return Err(OapiError::FileNotFoundError(self.file.clone()));
+        }
+
+        let filename = self
+            .file
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| OapiError::ResponseError("Invalid file name".to_string()))?
+            .to_string();
+        let mut file = tokio::fs::File::open(&self.file)
+            .await
+            .map_err(OapiError::FileReadError)?;
+        let bytes = file
+            .metadata()
+            .await
+            .map_err(OapiError::FileReadError)?
+            .len();
+
+        let upload = CreateUploadRequest {
+            bytes,
+            filename,
+            mime_type: self.mime_type.clone(),
+            purpose: self.purpose.clone(),
+        }
+        .get_response(&join_url(base_url, "uploads"), key)
+        .await?;
+
+        let parts_url = join_url(base_url, &format!("uploads/{}/parts", upload.id));
+        let mut part_ids = Vec::new();
+        let mut remaining = bytes;
+        while remaining > 0 {
+            let size = remaining.min(self.part_size as u64);
+            let mut data = vec![0; size as usize];
+            file.read_exact(&mut data)
+                .await
+                .map_err(OapiError::FileReadError)?;
+            remaining -= size;
+
+            let part = self
+                .add_part(AddUploadPartRequest { data: data.into() }, &parts_url, key)
+                .await?;
+            part_ids.push(part.id);
+        }
+
+        let upload = CompleteUploadRequest {
+            part_ids,
+            md5: None,
+        }
+        .get_response(
+            &join_url(base_url, &format!("uploads/{}/complete", upload.id)),
+            key,
+        )
+        .await?;
+
+        upload.file.ok_or_else(|| {
+            OapiError::ResponseError(format!("Upload {} was completed without a file", upload.id))
+        })
+    }
+
+    async fn add_part(
+        &self,
+        request: AddUploadPartRequest,
+        url: &str,
+        key: &str,
+    ) -> Result<response::UploadPart, OapiError> {
+        let mut attempt = 0;
+        loop {
+            match request.get_response(url, key).await {
+                Err(e) if attempt < self.max_retries && is_retryable(&e) => {
+                    attempt += 1;
+                    tokio::time::sleep(self.retry_delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Whether adding a part may succeed when sent again.
+///
+/// Besides the failures of [`RetryPolicy::is_retryable`], this retries
+/// timeouts, lost connections and every server error. Unlike a completion, a
+/// part that was processed before its response was lost is neither billed nor
+/// used: its ID is not in the `part_ids` of [`CompleteUploadRequest`], so
+/// completing the upload ignores it.
+fn is_retryable(error: &OapiError) -> bool {
+    RetryPolicy::is_retryable(error)
+        || matches!(
+            error,
+            OapiError::SendError(_) | OapiError::Timeout(_) | OapiError::ResponseError(_)
+        )
+        || error.status().is_some_and(|status| status >= 500)
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+    use crate::test_support::{MockResponse, MockServer};
+
+    const UPLOAD: &str = r#"{"id":"upload_abc","object":"upload","bytes":10,"created_at":1719184911,"filename":"training.jsonl","purpose":"fine-tune","status":"pending","expires_at":1719127296}"#;
+    const COMPLETED: &str = r#"{"id":"upload_abc","object":"upload","bytes":10,"created_at":1719184911,"filename":"training.jsonl","purpose":"fine-tune","status":"completed","expires_at":1719127296,"file":{"id":"file-xyz","object":"file","bytes":10,"created_at":1719186911,"filename":"training.jsonl","purpose":"fine-tune"}}"#;
+
+    fn part(id: &str) -> MockResponse {
+        MockResponse::json(
+            200,
+            &format!(
+                r#"{{"id":"{}","object":"upload.part","created_at":1719185911,"upload_id":"upload_abc"}}"#,
+                id
+            ),
+        )
+    }
+
+    #[tokio::test]
+    async fn multi_part_upload() {
+        let server = MockServer::start(vec![
+            MockResponse::json(200, UPLOAD),
+            MockResponse::json(500, "{}"),
+            part("part_1"),
+            part("part_2"),
+            part("part_3"),
+            MockResponse::json(200, COMPLETED),
+        ])
+        .await;
+
+        let path =
+            std::env::temp_dir().join(format!("openai-interface-upload-{}", std::process::id()));
+        std::fs::write(&path, "0123456789").unwrap();
+
+        let upload = ChunkedUpload {
+            file: path.clone(),
+            purpose: FilePurpose::FineTune,
+            part_size: 4,
+            retry_delay: Duration::ZERO,
+            ..Default::default()
+        };
+        let file = upload.upload(&server.url("/v1"), "key").await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(file.id, "file-xyz");
+
+        let requests = server.requests();
+        let paths: Vec<&str> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/v1/uploads",
+                "/v1/uploads/upload_abc/parts",
+                "/v1/uploads/upload_abc/parts",
+                "/v1/uploads/upload_abc/parts",
+                "/v1/uploads/upload_abc/parts",
+                "/v1/uploads/upload_abc/complete",
+            ]
+        );
+        assert_eq!(requests[0].body_json()["bytes"], 10);
+
+        // The first part failed once and was sent again with the same bytes.
+        let contains = |body: &[u8], data: &[u8]| body.windows(data.len()).any(|w| w == data);
+        assert!(contains(&requests[1].body, b"0123"));
+        assert!(contains(&requests[2].body, b"0123"));
+        assert!(contains(&requests[3].body, b"4567"));
+        assert!(contains(&requests[4].body, b"89"));
+
+        assert_eq!(
+            requests[5].body_json()["part_ids"],
+            serde_json::json!(["part_1", "part_2", "part_3"])
+        );
+    }
+
+    #[tokio::test]
+    async fn client_errors_are_not_retried() {
+        let server = MockServer::start(vec![
+            MockResponse::json(200, UPLOAD),
+            MockResponse::json(400, "{}"),
+        ])
+        .await;
+
+        let path = std::env::temp_dir().join(format!(
+            "openai-interface-upload-400-{}",
+            std::process::id()
+        ));
+        std::fs::write(&path, "0123456789").unwrap();
+
+        let upload = ChunkedUpload {
+            file: path.clone(),
+            part_size: 4,
+            retry_delay: Duration::ZERO,
+            ..Default::default()
+        };
+        let result = upload.upload(&server.url("/v1"), "key").await;
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.unwrap_err().status(), Some(400));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn retry_refused_connection() {
+        // Nothing listens on the port until after the first attempt.
+        let address = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .to_string();
+        let server = tokio::spawn({
+            let address = address.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                MockServer::start_at(&address, vec![part("part_1")]).await
+            }
+        });
+
+        let upload = ChunkedUpload {
+            retry_delay: Duration::from_millis(500),
+            ..Default::default()
+        };
+        let part = upload
+            .add_part(
+                AddUploadPartRequest {
+                    data: Bytes::from_static(b"0123"),
+                },
+                &format!("http://{}/v1/uploads/upload_abc/parts", address),
+                "key",
+            )
+            .await
+            .unwrap();
+        assert_eq!(part.id, "part_1");
+        assert_eq!(server.await.unwrap().requests().len(), 1);
+    }
+}
@@ -0,0 +1,117 @@
+use bytes::Bytes;
+use reqwest::header::HeaderMap;
+use serde::Serialize;
+
+use super::response::{Upload, UploadPart};
+use crate::errors::{ApiError, OapiError};
+use crate::files::create::request::FilePurpose;
+use crate::rest::post::{NoStream, Post, multipart_headers};
+
+/// Creates an intermediate [`Upload`] that parts can be added to, usually at
+/// `/uploads`.
+///
+/// Once all parts are added, complete the upload with a
+/// [`CompleteUploadRequest`]. An upload can accept at most 8 GB in total and
+/// expires after an hour.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct CreateUploadRequest {
+    /// The number of bytes in the file you are uploading.
+    pub bytes: u64,
+    /// The name of the file to upload.
+    pub filename: String,
+    /// The MIME type of the file.
+    ///
+    /// This must fall within the supported MIME types for your file purpose.
+    pub mime_type: String,
+    /// The intended purpose of the uploaded file.
+    pub purpose: FilePurpose,
+}
+
+/// Adds a part to an [`Upload`], at `/uploads/{upload_id}/parts`.
+///
+/// Each part can be at most 64 MB. Parts can be added in parallel; their order
+/// is decided when completing the upload.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct AddUploadPartRequest {
+    /// The chunk of bytes for this part. Retries of the request share it
+    /// instead of copying it.
+    #[serde(skip_serializing)]
+    pub data: Bytes,
+}
+
+/// Completes an [`Upload`], at `/uploads/{upload_id}/complete`.
+///
+/// The returned upload carries the finished file, which is ready to be used
+/// like any other file.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct CompleteUploadRequest {
+    /// The ordered list of part IDs.
+    pub part_ids: Vec<String>,
+    /// The optional md5 checksum for the file contents to verify if the bytes
+    /// uploaded match what you expect.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub md5: Option<String>,
+}
+
+impl Post for CreateUploadRequest {
+    #[inline]
+    fn is_streaming(&self) -> bool {
+        false
+    }
+
+    fn path(&self) -> Option<String> {
+        Some("/uploads".to_string())
+    }
+}
+
+impl NoStream for CreateUploadRequest {
+    type Response = Upload;
+}
+
+impl Post for AddUploadPartRequest {
+    #[inline]
+    fn is_streaming(&self) -> bool {
+        false
+    }
+}
+
+impl NoStream for AddUploadPartRequest {
+    type Response = UploadPart;
+
+    /// Sends the part as multipart/form-data.
+    async fn get_raw_response(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        key: &str,
+        headers: &HeaderMap,
+    ) -> Result<reqwest::Response, OapiError> {
+        let part =
+            reqwest::multipart::Part::stream_with_length(self.data.clone(), self.data.len() as u64)
+                .file_name("part");
+        let form = reqwest::multipart::Form::new().part("data", part);
+
+        let response = client
+            .post(url)
+            .headers(multipart_headers(key, headers)?)
+            .multipart(form)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(ApiError::from_response(response).await.into());
+        }
+        Ok(response)
+    }
+}
+
+impl Post for CompleteUploadRequest {
+    #[inline]
+    fn is_streaming(&self) -> bool {
+        false
+    }
+}
+
+impl NoStream for CompleteUploadRequest {
+    type Response = Upload;
+}
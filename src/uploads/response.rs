@@ -0,0 +1,70 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::errors::OapiError;
+use crate::files::create::response::{FileObject, FilePurpose};
+
+/// An upload that parts can be added to, and that becomes a file once it is
+/// completed.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Upload {
+    /// The Upload unique identifier, which can be referenced in API endpoints.
+    pub id: String,
+    /// The intended number of bytes to be uploaded.
+    pub bytes: u64,
+    /// The Unix timestamp (in seconds) for when the Upload was created.
+    pub created_at: u64,
+    /// The Unix timestamp (in seconds) for when the Upload will expire.
+    pub expires_at: u64,
+    /// The name of the file to be uploaded.
+    pub filename: String,
+    /// The object type, which is always `upload`.
+    pub object: String,
+    /// The intended purpose of the file.
+    pub purpose: FilePurpose,
+    /// The status of the Upload.
+    pub status: UploadStatus,
+    /// The ready File object after the Upload is completed.
+    pub file: Option<FileObject>,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum UploadStatus {
+    Pending,
+    Completed,
+    Cancelled,
+    Expired,
+    #[serde(untagged)]
+    Other(String),
+}
+
+/// A chunk of bytes added to an [`Upload`].
+#[derive(Debug, Deserialize, Clone)]
+pub struct UploadPart {
+    /// The upload Part unique identifier, which can be referenced in API endpoints.
+    pub id: String,
+    /// The Unix timestamp (in seconds) for when the Part was created.
+    pub created_at: u64,
+    /// The ID of the Upload object that this Part was added to.
+    pub upload_id: String,
+    /// The object type, which is always `upload.part`.
+    pub object: String,
+}
+
+impl FromStr for Upload {
+    type Err = OapiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s).map_err(OapiError::from)
+    }
+}
+
+impl FromStr for UploadPart {
+    type Err = OapiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s).map_err(OapiError::from)
+    }
+}